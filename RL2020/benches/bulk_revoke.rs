@@ -0,0 +1,59 @@
+#![allow(non_snake_case)]
+
+//Confronto tra la revoca di massa con una sola codifica finale e la codifica
+//ripetuta dopo ogni revoca (comportamento precedente di `update`).
+//
+//Eseguire con: cargo bench --bench bulk_revoke
+
+use std::time::{Duration, Instant};
+
+use ::RevocationList2020::RevocationList2020;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+const LIST_SIZE_KB: usize = 128;
+const REVOCATIONS: u64 = 2_000;
+
+fn indices() -> impl Iterator<Item = u64>
+{
+    //Indici sparsi sull'intera lista da 128kb
+    (0..REVOCATIONS).map(|i| (i * 524_287) % (LIST_SIZE_KB as u64 * 1024 * 8))
+}
+
+fn lazy() -> Duration
+{
+    let mut rl = RevocationList2020::new(LIST_ID, LIST_SIZE_KB).unwrap();
+    let start = Instant::now();
+
+    for i in indices()
+    {
+        rl.revoke(i).unwrap();
+    }
+    rl.encode().unwrap();
+
+    start.elapsed()
+}
+
+fn eager() -> Duration
+{
+    let mut rl = RevocationList2020::new(LIST_ID, LIST_SIZE_KB).unwrap();
+    let start = Instant::now();
+
+    for i in indices()
+    {
+        rl.revoke(i).unwrap();
+        rl.encode().unwrap();
+    }
+
+    start.elapsed()
+}
+
+fn main()
+{
+    let lazy = lazy();
+    let eager = eager();
+
+    println!("{} revocations on a {}kb list", REVOCATIONS, LIST_SIZE_KB);
+    println!("  encode once:         {:?}", lazy);
+    println!("  encode every update: {:?}", eager);
+    println!("  speedup:             {:.1}x", eager.as_secs_f64() / lazy.as_secs_f64());
+}
@@ -0,0 +1,49 @@
+#![allow(non_snake_case)]
+
+//Confronto tra la lettura degli indici revocati con `is_revoked` su ogni indice
+//e con gli iteratori che leggono la bitstring una parola alla volta.
+//
+//Eseguire con: cargo bench --bench iterate
+
+use std::time::{Duration, Instant};
+
+use ::RevocationList2020::RevocationList2020;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+const LIST_SIZE_KB: usize = 128;
+const REVOCATIONS: u64 = 2_000;
+
+fn list() -> RevocationList2020
+{
+    //Indici sparsi sull'intera lista da 128kb
+    let mut rl = RevocationList2020::new(LIST_ID, LIST_SIZE_KB).unwrap();
+    rl.revoke_many((0..REVOCATIONS).map(|i| (i * 524_287) % (LIST_SIZE_KB as u64 * 1024 * 8))).unwrap();
+    rl
+}
+
+fn point_queries(rl: &RevocationList2020) -> (Duration, usize)
+{
+    let start = Instant::now();
+    let revoked = (0..rl.capacity() as u64).filter(|i| rl.is_revoked(*i).unwrap()).count();
+    (start.elapsed(), revoked)
+}
+
+fn iterator(rl: &RevocationList2020) -> (Duration, usize)
+{
+    let start = Instant::now();
+    let revoked = rl.iter_revoked().count();
+    (start.elapsed(), revoked)
+}
+
+fn main()
+{
+    let rl = list();
+    let (queries, expected) = point_queries(&rl);
+    let (iterated, revoked) = iterator(&rl);
+    assert_eq!(revoked, expected);
+
+    println!("{} revoked entries on a {}kb list", revoked, LIST_SIZE_KB);
+    println!("  is_revoked on every index: {:?}", queries);
+    println!("  iter_revoked:              {:?}", iterated);
+    println!("  speedup:                   {:.1}x", queries.as_secs_f64() / iterated.as_secs_f64());
+}
@@ -0,0 +1,523 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::io::Write;
+
+use base64::{decode_config, encode_config, STANDARD, URL_SAFE_NO_PAD};
+use flate2::read::GzDecoder;
+use flate2::read::ZlibDecoder;
+use flate2::write::GzEncoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::capacity_to_u64;
+use crate::index_to_usize;
+use crate::BitOrder;
+use crate::CodingStage;
+use crate::CredentialError;
+use crate::Result;
+
+
+//Prefisso multibase della codifica base64url senza padding
+const MULTIBASE_BASE64URL: char = 'u';
+
+
+//Compressione e codifica testuale della bitstring nella `encodedList`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec
+{
+    //RevocationList2020: ZLIB compressed, base64 encoded
+    ZlibBase64,
+
+    //StatusList2021: GZIP compressed, base64url encoded without padding
+    GzipBase64Url,
+
+    //Bitstring Status List: GZIP compressed, multibase base64url (prefisso 'u')
+    GzipMultibase,
+}
+
+
+
+//The bitstring shared by all status list formats.
+//Non è serializzabile da sola: i formati che la salvano (Snapshot, IssuerStore) conservano anche l'ordine dei bit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bitstring
+{
+    bits: Vec<u8>,
+
+    order: BitOrder,
+}
+
+
+impl Bitstring
+{
+    //Creates a bitstring of `len` bytes with every bit set to 0
+    pub fn new(len: usize) -> Self
+    {
+        Self::from_bytes(vec![0; len])
+    }
+
+    //Wraps raw bytes, using the spec-conformant bit order
+    pub fn from_bytes(bits: Vec<u8>) -> Self
+    {
+        Bitstring
+        {
+            bits,
+            order: BitOrder::default(),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8]
+    {
+        &self.bits
+    }
+
+    //Numero di byte della bitstring
+    pub fn byte_len(&self) -> usize
+    {
+        self.bits.len()
+    }
+
+    //Returns the number of addressable bits
+    pub fn capacity(&self) -> usize
+    {
+        self.bits.len() * 8
+    }
+
+    pub fn order(&self) -> BitOrder
+    {
+        self.order
+    }
+
+    pub fn set_order(&mut self, order: BitOrder)
+    {
+        self.order = order;
+    }
+
+
+    pub fn check_bounds(&self, index: u64) -> Result<()>
+    {
+        let capacity = self.capacity();
+        if index_to_usize(index)? >= capacity
+        {
+            return Err(CredentialError::IndexOutOfRange
+            {
+                index,
+                capacity: capacity_to_u64(capacity),
+            });
+        }
+        Ok(())
+    }
+
+
+    //Returns the bit at `index`
+    pub fn get(&self, index: u64) -> Result<bool>
+    {
+        self.check_bounds(index)?;
+        Ok(self.bit(index))
+    }
+
+    //Sets the bit at `index` to `value`, returning its previous value
+    pub fn set(&mut self, index: u64, value: bool) -> Result<bool>
+    {
+        self.check_bounds(index)?;
+        Ok(self.put(index, value))
+    }
+
+
+    //Iterates the indices of the set bits in ascending order, skipping unset bits 64 at a time
+    pub fn ones(&self) -> Ones<'_>
+    {
+        Ones { bits: &self.bits, order: self.order, pos: 0, current: 0 }
+    }
+
+    //Iterates the runs of consecutive set bits as closed ranges [start, end]
+    pub fn one_ranges(&self) -> OneRanges<'_>
+    {
+        OneRanges { ones: self.ones().peekable() }
+    }
+
+    //Returns the number of set bits
+    pub fn count_ones(&self) -> u64
+    {
+        let words = self.bits.chunks_exact(8);
+        let tail: u64 = words.remainder().iter().map(|byte| byte.count_ones() as u64).sum();
+        words.map(|word| u64::from_ne_bytes(word.try_into().expect("chunks of 8 bytes")).count_ones() as u64).sum::<u64>() + tail
+    }
+
+    //Iterates every bit in index order
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_
+    {
+        let order = self.order;
+        self.bits.iter().flat_map(move |byte| (0..8).map(move |offset| byte & order.mask(offset) != 0))
+    }
+
+
+    //Applies all `changes` in order, after checking every index: on error the bitstring is untouched.
+    //Returns, sorted, the indices whose final value differs from the initial one.
+    pub fn apply(&mut self, changes: Vec<(u64, bool)>) -> Result<Vec<u64>>
+    {
+        for (index, _) in &changes
+        {
+            self.check_bounds(*index)?;
+        }
+
+        //Valore iniziale di ogni indice toccato
+        let mut initial: BTreeMap<u64, bool> = BTreeMap::new();
+        for (index, value) in changes
+        {
+            let before = self.put(index, value);
+            initial.entry(index).or_insert(before);
+        }
+
+        Ok(initial
+            .into_iter()
+            .filter(|(index, before)| self.bit(*index) != *before)
+            .map(|(index, _)| index)
+            .collect())
+    }
+
+
+    //Reads the `size` bits starting at `first` as an unsigned integer, most significant bit first
+    pub fn get_bits(&self, first: u64, size: u8) -> Result<u64>
+    {
+        self.check_range(first, size)?;
+        Ok((0..size as u64).fold(0, |value, i| (value << 1) | self.bit(first + i) as u64))
+    }
+
+    //Writes `value` into the `size` bits starting at `first`, returning the previous value
+    pub fn set_bits(&mut self, first: u64, size: u8, value: u64) -> Result<u64>
+    {
+        self.check_range(first, size)?;
+        if size < 64 && value >> size != 0
+        {
+            return Err(CredentialError::StatusValueOutOfRange { value, status_size: size });
+        }
+
+        let before = self.get_bits(first, size)?;
+        for i in 0..size as u64
+        {
+            self.put(first + i, (value >> (size as u64 - 1 - i)) & 1 == 1);
+        }
+        Ok(before)
+    }
+
+    fn check_range(&self, first: u64, size: u8) -> Result<()>
+    {
+        if size == 0 || size > 64
+        {
+            return Err(CredentialError::InvalidStatusSize(size));
+        }
+        self.check_bounds(first)?;
+        let last = first.checked_add(size as u64 - 1).ok_or(CredentialError::IndexNotAddressable { index: first })?;
+        self.check_bounds(last)
+    }
+
+
+    //Posizione del byte che contiene il bit, `index` deve essere già controllato da `check_bounds`
+    fn byte_pos(index: u64) -> usize
+    {
+        usize::try_from(index / 8).expect("index checked by check_bounds")
+    }
+
+    //Lettura del bit senza controllo dei limiti
+    pub(crate) fn bit(&self, index: u64) -> bool
+    {
+        let pos = Self::byte_pos(index);
+        let mask = self.order.mask((index % 8) as u8);
+
+        self.bits[pos] & mask != 0
+    }
+
+    //Scrittura del bit senza controllo dei limiti, ritorna il valore precedente
+    pub(crate) fn put(&mut self, index: u64, value: bool) -> bool
+    {
+        let before = self.bit(index);
+        let pos = Self::byte_pos(index);
+        let mask = self.order.mask((index % 8) as u8);
+
+        if value
+        {
+            self.bits[pos] |= mask;
+        }
+        else
+        {
+            self.bits[pos] &= !mask;
+        }
+        before
+    }
+
+
+
+    //Compresses and encodes the bitstring as an `encodedList` value
+    pub fn pack(&self, codec: Codec) -> Result<String>
+    {
+        match codec
+        {
+            Codec::ZlibBase64 =>
+            {
+                let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
+                e.write_all(&self.bits).map_err(|e| CredentialError::encoding(CodingStage::Zlib, e))?;
+                let compressed = e.finish().map_err(|e| CredentialError::encoding(CodingStage::Zlib, e))?;
+                Ok(encode_config(&compressed, STANDARD))
+            }
+            Codec::GzipBase64Url =>
+            {
+                let mut e = GzEncoder::new(Vec::new(), Compression::default());
+                e.write_all(&self.bits).map_err(|e| CredentialError::encoding(CodingStage::Gzip, e))?;
+                let compressed = e.finish().map_err(|e| CredentialError::encoding(CodingStage::Gzip, e))?;
+                Ok(encode_config(&compressed, URL_SAFE_NO_PAD))
+            }
+            Codec::GzipMultibase =>
+            {
+                let encoded = self.pack(Codec::GzipBase64Url)?;
+                Ok(format!("{}{}", MULTIBASE_BASE64URL, encoded))
+            }
+        }
+    }
+
+
+    //Decodes and decompresses an `encodedList` value
+    pub fn unpack(data: &str, codec: Codec) -> Result<Self>
+    {
+        let mut buf = Vec::new();
+
+        match codec
+        {
+            Codec::ZlibBase64 =>
+            {
+                let bin = decode_config(data, STANDARD).map_err(|e| CredentialError::decoding(CodingStage::Base64, e))?;
+                ZlibDecoder::new(&*bin)
+                    .read_to_end(&mut buf)
+                    .map_err(|e| CredentialError::decoding(CodingStage::Zlib, e))?;
+            }
+            Codec::GzipBase64Url =>
+            {
+                //Alcune implementazioni includono il padding
+                let bin = decode_config(data.trim_end_matches('='), URL_SAFE_NO_PAD)
+                    .map_err(|e| CredentialError::decoding(CodingStage::Base64, e))?;
+                GzDecoder::new(&*bin)
+                    .read_to_end(&mut buf)
+                    .map_err(|e| CredentialError::decoding(CodingStage::Gzip, e))?;
+            }
+            Codec::GzipMultibase =>
+            {
+                let encoded = data.strip_prefix(MULTIBASE_BASE64URL).ok_or_else(||
+                {
+                    CredentialError::decoding(
+                        CodingStage::Multibase,
+                        format!("expected base64url prefix '{}'", MULTIBASE_BASE64URL),
+                    )
+                })?;
+                return Self::unpack(encoded, Codec::GzipBase64Url);
+            }
+        }
+
+        Ok(Self::from_bytes(buf))
+    }
+}
+
+
+
+//A bitstring together with its `encodedList`, shared by the list formats: the encoded value is cached
+//and recomputed with `codec` only by `encode()` after the bitstring changed.
+#[derive(Debug, Clone)]
+pub struct EncodedList
+{
+    codec: Codec,
+
+    //Cache della bitstring codificata, aggiornata solo da `encode()`
+    encoded: String,
+
+    bits: Bitstring,
+
+    //true se `bits` è stato modificato dopo l'ultima codifica
+    dirty: bool,
+}
+
+
+impl EncodedList
+{
+    //Wraps `bits`, encoding them right away
+    pub fn new(bits: Bitstring, codec: Codec) -> Result<Self>
+    {
+        let encoded = bits.pack(codec)?;
+        Ok(EncodedList { codec, encoded, bits, dirty: false })
+    }
+
+    //Decodes a published `encodedList`, which is kept as the cached value
+    pub fn unpack(encoded: &str, codec: Codec) -> Result<Self>
+    {
+        let bits = Bitstring::unpack(encoded, codec)?;
+        Ok(EncodedList { codec, encoded: encoded.to_string(), bits, dirty: false })
+    }
+
+    //Wraps `bits` without encoding them, as if they had been modified after the last `encode()`
+    pub(crate) fn modified(bits: Bitstring, codec: Codec) -> Self
+    {
+        EncodedList { codec, encoded: String::new(), bits, dirty: true }
+    }
+
+
+    pub fn bits(&self) -> &Bitstring
+    {
+        &self.bits
+    }
+
+    pub fn codec(&self) -> Codec
+    {
+        self.codec
+    }
+
+    //Il valore codificato non dipende dall'ordine dei bit, che cambia solo il modo di indirizzarli
+    pub fn set_order(&mut self, order: BitOrder)
+    {
+        self.bits.set_order(order);
+    }
+
+
+    //Returns the encoded list, recomputed without caching if the bitstring changed since the last `encode()`
+    pub fn get_encList(&self) -> Result<String>
+    {
+        if self.dirty
+        {
+            return self.bits.pack(self.codec);
+        }
+
+        Ok(self.encoded.clone())
+    }
+
+    //Compresses and encodes the bitstring if it changed since the last call and caches the result
+    pub fn encode(&mut self) -> Result<&str>
+    {
+        if self.dirty
+        {
+            self.encoded = self.bits.pack(self.codec)?;
+            self.dirty = false;
+        }
+
+        Ok(&self.encoded)
+    }
+
+    //Returns `true` if the cached encoded list is out of date
+    pub fn is_dirty(&self) -> bool
+    {
+        self.dirty
+    }
+
+
+    //Sets the bit at `index`, returning its previous value
+    pub fn set(&mut self, index: u64, value: bool) -> Result<bool>
+    {
+        let before = self.bits.set(index, value)?;
+        if before != value
+        {
+            self.dirty = true;
+        }
+        Ok(before)
+    }
+
+    //Writes the `size` bits starting at `first`, returning their previous value
+    pub fn set_bits(&mut self, first: u64, size: u8, value: u64) -> Result<u64>
+    {
+        let before = self.bits.set_bits(first, size, value)?;
+        if before != value
+        {
+            self.dirty = true;
+        }
+        Ok(before)
+    }
+
+    //Same as `Bitstring::apply`
+    pub fn apply(&mut self, changes: Vec<(u64, bool)>) -> Result<Vec<u64>>
+    {
+        self.apply_with(|bits| bits.apply(changes))
+    }
+
+    //Runs `update` on the bitstring, which returns the entries it changed: the cache is stale only if there are any
+    pub(crate) fn apply_with<F>(&mut self, update: F) -> Result<Vec<u64>>
+    where
+    F: FnOnce(&mut Bitstring) -> Result<Vec<u64>>,
+    {
+        let changed = update(&mut self.bits)?;
+        if !changed.is_empty()
+        {
+            self.dirty = true;
+        }
+        Ok(changed)
+    }
+}
+
+
+//Iterator over the indices of the set bits, see `Bitstring::ones`
+#[derive(Debug, Clone)]
+pub struct Ones<'a>
+{
+    bits: &'a [u8],
+    order: BitOrder,
+
+    //Prossimo byte da leggere
+    pos: usize,
+
+    //Bit ancora da restituire del byte `pos - 1`, riportati in ordine Lsb0
+    current: u8,
+}
+
+impl Iterator for Ones<'_>
+{
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64>
+    {
+        while self.current == 0
+        {
+            //Le parole di 8 byte tutti a zero vengono saltate con un solo confronto
+            while let Some(word) = self.bits.get(self.pos..self.pos + 8)
+            {
+                if u64::from_ne_bytes(word.try_into().expect("8 bytes")) != 0
+                {
+                    break;
+                }
+                self.pos += 8;
+            }
+
+            let byte = *self.bits.get(self.pos)?;
+            self.pos += 1;
+            self.current = match self.order
+            {
+                BitOrder::Msb0 => byte.reverse_bits(),
+                BitOrder::Lsb0 => byte,
+            };
+        }
+
+        let offset = self.current.trailing_zeros() as u64;
+        self.current &= self.current - 1;
+        Some(capacity_to_u64(self.pos - 1) * 8 + offset)
+    }
+}
+
+
+//Iterator over the runs of set bits, see `Bitstring::one_ranges`
+#[derive(Debug, Clone)]
+pub struct OneRanges<'a>
+{
+    ones: std::iter::Peekable<Ones<'a>>,
+}
+
+impl Iterator for OneRanges<'_>
+{
+    type Item = (u64, u64);
+
+    fn next(&mut self) -> Option<(u64, u64)>
+    {
+        let start = self.ones.next()?;
+        let mut end = start;
+        while self.ones.next_if_eq(&(end + 1)).is_some()
+        {
+            end += 1;
+        }
+        Some((start, end))
+    }
+}
@@ -0,0 +1,387 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use identity_core::common::Object;
+use identity_core::common::Value;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::capacity_to_u64;
+use crate::index_to_usize;
+use crate::Bitstring::Bitstring;
+use crate::Bitstring::Codec;
+use crate::Bitstring::EncodedList;
+use crate::CredentialError;
+use crate::Result;
+
+
+
+//Costanti tipo
+pub const BITSTRING_STATUS_LIST_TYPE: &str = "BitstringStatusList";
+
+//Minimum number of entries of a bitstring status list
+pub const MIN_STATUS_LIST_ENTRIES: usize = 131_072;
+
+
+
+//Scopo della lista. Rispetto a StatusList2021, Bitstring Status List definisce anche `refresh` e `message`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BitstringStatusPurpose
+{
+    Revocation,
+    Suspension,
+    Refresh,
+    Message,
+}
+
+impl BitstringStatusPurpose
+{
+    pub fn as_str(&self) -> &'static str
+    {
+        match self
+        {
+            BitstringStatusPurpose::Revocation => "revocation",
+            BitstringStatusPurpose::Suspension => "suspension",
+            BitstringStatusPurpose::Refresh => "refresh",
+            BitstringStatusPurpose::Message => "message",
+        }
+    }
+}
+
+impl Display for BitstringStatusPurpose
+{
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result
+    {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for BitstringStatusPurpose
+{
+    type Err = CredentialError;
+
+    fn from_str(s: &str) -> Result<Self>
+    {
+        match s
+        {
+            "revocation" => Ok(BitstringStatusPurpose::Revocation),
+            "suspension" => Ok(BitstringStatusPurpose::Suspension),
+            "refresh" => Ok(BitstringStatusPurpose::Refresh),
+            "message" => Ok(BitstringStatusPurpose::Message),
+            other => Err(CredentialError::InvalidStatus(format!("unknown status purpose '{}'", other))),
+        }
+    }
+}
+
+
+
+//Associazione tra un valore di status e il relativo messaggio (`statusMessage`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusMessage
+{
+    pub status: u64,
+    pub message: String,
+}
+
+impl StatusMessage
+{
+    pub fn new(status: u64, message: &str) -> Self
+    {
+        StatusMessage
+        {
+            status,
+            message: message.to_string(),
+        }
+    }
+
+    //Returns the JSON form `{"status": "0x1", "message": "..."}`
+    pub fn to_value(&self) -> Value
+    {
+        let mut object = Object::new();
+        object.insert("status".to_owned(), Value::String(format!("{:#x}", self.status)));
+        object.insert("message".to_owned(), Value::String(self.message.clone()));
+        Value::Object(object.into_iter().collect())
+    }
+
+    //Parses the JSON form of a status message
+    pub fn from_value(value: &Value) -> Result<Self>
+    {
+        let invalid = || CredentialError::InvalidStatus(format!("invalid status message {}", value));
+
+        let status = value.get("status").and_then(Value::as_str).ok_or_else(invalid)?;
+        let message = value.get("message").and_then(Value::as_str).ok_or_else(invalid)?;
+        let hex = status.strip_prefix("0x").ok_or_else(invalid)?;
+
+        Ok(StatusMessage
+        {
+            status: u64::from_str_radix(hex, 16).map_err(|_| invalid())?,
+            message: message.to_string(),
+        })
+    }
+}
+
+
+//Checks a `statusMessage` table against the status size: every value must fit and, when present,
+//the table must describe every possible value. It is mandatory for entries wider than 1 bit.
+pub(crate) fn check_messages(status_size: u8, messages: &[StatusMessage]) -> Result<()>
+{
+    if status_size == 0 || status_size > 64
+    {
+        return Err(CredentialError::InvalidStatusSize(status_size));
+    }
+
+    if messages.is_empty()
+    {
+        if status_size > 1
+        {
+            return Err(CredentialError::InvalidStatus(format!(
+                "statusMessage is required when statusSize is {}",
+                status_size
+            )));
+        }
+        return Ok(());
+    }
+
+    let expected = 1u64.checked_shl(status_size as u32);
+    if expected != Some(messages.len() as u64)
+    {
+        return Err(CredentialError::InvalidStatus(format!(
+            "statusMessage must list every value of a {}-bit status, got {} messages",
+            status_size,
+            messages.len()
+        )));
+    }
+
+    let mut seen: BTreeMap<u64, &str> = BTreeMap::new();
+    for m in messages
+    {
+        if status_size < 64 && m.status >> status_size != 0
+        {
+            return Err(CredentialError::StatusValueOutOfRange { value: m.status, status_size });
+        }
+        if seen.insert(m.status, &m.message).is_some()
+        {
+            return Err(CredentialError::InvalidStatus(format!("duplicate status message for {:#x}", m.status)));
+        }
+    }
+    Ok(())
+}
+
+
+
+//A W3C Bitstring Status List: entries of `status_size` bits, GZIP compressed and multibase encoded.
+#[derive(Debug, Clone)]
+pub struct BitstringStatusList
+{
+    id: String,
+
+    purpose: BitstringStatusPurpose,
+
+    status_size: u8,
+
+    messages: Vec<StatusMessage>,
+
+    list: EncodedList,
+}
+
+
+impl BitstringStatusList
+{
+    //The type name of the list
+    pub const TYPE: &'static str = BITSTRING_STATUS_LIST_TYPE;
+
+
+    // Constructs a new empty list of `entries` entries, each `status_size` bits wide.
+    pub fn new(id: &str, purpose: BitstringStatusPurpose, entries: usize, status_size: u8) -> Result<Self>
+    {
+        if id.trim().is_empty()
+        {
+            return Err(CredentialError::EmptyId);
+        }
+        if status_size == 0 || status_size > 64
+        {
+            return Err(CredentialError::InvalidStatusSize(status_size));
+        }
+        if entries < MIN_STATUS_LIST_ENTRIES
+        {
+            return Err(CredentialError::TooFewEntries { entries, min: MIN_STATUS_LIST_ENTRIES });
+        }
+
+        let bs = Bitstring::new((entries * status_size as usize).div_ceil(8));
+
+        Ok(BitstringStatusList
+        {
+            id: String::from(id),
+            purpose,
+            status_size,
+            messages: Vec::new(),
+            list: EncodedList::new(bs, Codec::GzipMultibase)?,
+        })
+    }
+
+
+    //Crea lista partendo da una encodedList pubblicata
+    pub fn new_withList(id: &str, purpose: BitstringStatusPurpose, status_size: u8, encList: &str) -> Result<Self>
+    {
+        if id.trim().is_empty()
+        {
+            return Err(CredentialError::EmptyId);
+        }
+        if status_size == 0 || status_size > 64
+        {
+            return Err(CredentialError::InvalidStatusSize(status_size));
+        }
+
+        let list = EncodedList::unpack(encList, Codec::GzipMultibase)?;
+
+        let entries = list.bits().capacity() / status_size as usize;
+        if entries < MIN_STATUS_LIST_ENTRIES
+        {
+            return Err(CredentialError::TooFewEntries { entries, min: MIN_STATUS_LIST_ENTRIES });
+        }
+
+        Ok(BitstringStatusList
+        {
+            id: String::from(id),
+            purpose,
+            status_size,
+            messages: Vec::new(),
+            list,
+        })
+    }
+
+
+    //Sets the `statusMessage` table, validated against the status size
+    pub fn with_messages(mut self, messages: Vec<StatusMessage>) -> Result<Self>
+    {
+        check_messages(self.status_size, &messages)?;
+        self.messages = messages;
+        Ok(self)
+    }
+
+
+    pub fn id(&self) -> &str
+    {
+        &self.id
+    }
+
+    pub fn purpose(&self) -> BitstringStatusPurpose
+    {
+        self.purpose
+    }
+
+    pub fn status_size(&self) -> u8
+    {
+        self.status_size
+    }
+
+    pub fn messages(&self) -> &[StatusMessage]
+    {
+        &self.messages
+    }
+
+    //Returns the number of entries of the list
+    pub fn capacity(&self) -> usize
+    {
+        self.list.bits().capacity() / self.status_size as usize
+    }
+
+
+    //Returns the encoded list, recomputed without caching if the bitstring changed since the last `encode()`
+    pub fn get_encList(&self) -> Result<String>
+    {
+        self.list.get_encList()
+    }
+
+    //Compresses and encodes the bitstring if needed and caches the result
+    pub fn encode(&mut self) -> Result<&str>
+    {
+        self.list.encode()
+    }
+
+    pub fn is_dirty(&self) -> bool
+    {
+        self.list.is_dirty()
+    }
+
+
+    //Returns the position of the first bit of the entry at `index`
+    fn check_index(&self, index: u64) -> Result<u64>
+    {
+        let capacity = self.capacity();
+        if index_to_usize(index)? >= capacity
+        {
+            return Err(CredentialError::IndexOutOfRange { index, capacity: capacity_to_u64(capacity) });
+        }
+        index
+            .checked_mul(self.status_size as u64)
+            .ok_or(CredentialError::IndexNotAddressable { index })
+    }
+
+    //Returns the status value of the entry at `index`
+    pub fn get(&self, index: u64) -> Result<u64>
+    {
+        let first = self.check_index(index)?;
+        self.list.bits().get_bits(first, self.status_size)
+    }
+
+    //Sets the status value of the entry at `index`
+    pub fn set(&mut self, index: u64, value: u64) -> Result<()>
+    {
+        let first = self.check_index(index)?;
+        self.list.set_bits(first, self.status_size, value)?;
+        Ok(())
+    }
+
+    //Returns the message associated with the status of the entry at `index`, if the list has a message table
+    pub fn message(&self, index: u64) -> Result<Option<&str>>
+    {
+        let value = self.get(index)?;
+        Ok(self.messages.iter().find(|m| m.status == value).map(|m| m.message.as_str()))
+    }
+
+
+    //Applies all `changes` in order after validating every index and value, so on error the list is untouched.
+    //Returns, sorted, the indices whose final value differs from the initial one.
+    pub fn apply<I>(&mut self, changes: I) -> Result<Vec<u64>>
+    where
+    I: IntoIterator<Item = (u64, u64)>,
+    {
+        let size = self.status_size;
+        let mut positions = Vec::new();
+        for (index, value) in changes
+        {
+            let first = self.check_index(index)?;
+            if size < 64 && value >> size != 0
+            {
+                return Err(CredentialError::StatusValueOutOfRange { value, status_size: size });
+            }
+            positions.push((index, first, value));
+        }
+
+        self.list.apply_with(|bits|
+        {
+            //Primo bit e valore iniziale di ogni indice toccato
+            let mut initial: BTreeMap<u64, (u64, u64)> = BTreeMap::new();
+            for (index, first, value) in positions
+            {
+                let before = bits.set_bits(first, size, value)?;
+                initial.entry(index).or_insert((first, before));
+            }
+
+            let mut changed = Vec::new();
+            for (index, (first, before)) in initial
+            {
+                if bits.get_bits(first, size)? != before
+                {
+                    changed.push(index);
+                }
+            }
+            Ok(changed)
+        })
+    }
+}
@@ -0,0 +1,187 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::str::FromStr;
+
+use identity_core::common::Object;
+use identity_core::common::Url;
+use identity_core::common::Value;
+use identity_did::did::DIDUrl;
+use identity_did::did::DID;
+
+use identity_credential::credential::Status;
+
+use crate::BitstringStatusList::check_messages;
+use crate::BitstringStatusList::BitstringStatusPurpose;
+use crate::BitstringStatusList::StatusMessage;
+use crate::CredentialStatus::status_index;
+use crate::CredentialStatus::status_list_credential;
+use crate::CredentialError;
+use crate::Result;
+
+
+
+//A `credentialStatus` entry pointing into a Bitstring Status List credential.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitstringStatusListEntry(Status, Url);
+
+
+
+impl BitstringStatusListEntry
+{
+  const PURPOSE_PROPERTY_NAME: &'static str = "statusPurpose";
+  const INDEX_PROPERTY_NAME: &'static str = "statusListIndex";
+  const CREDENTIAL_PROPERTY_NAME: &'static str = "statusListCredential";
+  const SIZE_PROPERTY_NAME: &'static str = "statusSize";
+  const MESSAGE_PROPERTY_NAME: &'static str = "statusMessage";
+
+
+  //The type name of the status entry.
+  pub const TYPE: &'static str = "BitstringStatusListEntry";
+
+  //Creates a new `BitstringStatusListEntry`. `statusSize` and `statusMessage` are omitted for plain 1-bit entries.
+  pub fn new<D: DID>(
+    id: DIDUrl<D>,
+    purpose: BitstringStatusPurpose,
+    index: u64,
+    urlLista: Url,
+    status_size: u8,
+    messages: &[StatusMessage],
+  ) -> Result<Self>
+  {
+    check_messages(status_size, messages)?;
+
+    let mut object = Object::new();
+    object.insert(Self::PURPOSE_PROPERTY_NAME.to_owned(), Value::String(purpose.to_string()));
+    object.insert(Self::INDEX_PROPERTY_NAME.to_owned(), Value::String(index.to_string()));
+    object.insert(Self::CREDENTIAL_PROPERTY_NAME.to_owned(), Value::String(urlLista.to_string()));
+
+    if status_size > 1
+    {
+      object.insert(Self::SIZE_PROPERTY_NAME.to_owned(), Value::from(status_size));
+    }
+    if !messages.is_empty()
+    {
+      object.insert(
+        Self::MESSAGE_PROPERTY_NAME.to_owned(),
+        Value::Array(messages.iter().map(StatusMessage::to_value).collect()),
+      );
+    }
+
+    Ok(BitstringStatusListEntry(
+      Status::new_with_properties(Url::from(id), Self::TYPE.to_owned(), object),
+      urlLista,
+    ))
+  }
+
+  //Returns the [`DIDUrl`] of the status entry.
+  pub fn id<D: DID>(&self) -> Result<DIDUrl<D>>
+  {
+    DIDUrl::parse(self.0.id.as_str())
+      .map_err(|err| CredentialError::InvalidStatus(format!("invalid DID Url '{}': {:?}", self.0.id, err)))
+  }
+
+  //Returns the purpose of the referenced status list.
+  pub fn purpose(&self) -> Result<BitstringStatusPurpose>
+  {
+    match self.0.properties.get(Self::PURPOSE_PROPERTY_NAME)
+    {
+      Some(Value::String(purpose)) => BitstringStatusPurpose::from_str(purpose),
+      _ => Err(CredentialError::InvalidStatus(format!(
+        "expected {} to be a string",
+        Self::PURPOSE_PROPERTY_NAME
+      ))),
+    }
+  }
+
+  //Returns the location of the status list credential.
+  pub fn list_credential(&self) -> &Url
+  {
+    &self.1
+  }
+
+  //Returns the index of the credential in the issuer's status list if it can be decoded.
+  pub fn index(&self) -> Result<u64>
+  {
+    status_index(&self.0.properties, Self::INDEX_PROPERTY_NAME)
+  }
+
+  //Returns the width in bits of the status entry, 1 when `statusSize` is absent.
+  pub fn status_size(&self) -> Result<u8>
+  {
+    match self.0.properties.get(Self::SIZE_PROPERTY_NAME)
+    {
+      None => Ok(1),
+      Some(Value::Number(size)) => size
+        .as_u64()
+        .and_then(|size| u8::try_from(size).ok())
+        .filter(|size| (1..=64).contains(size))
+        .ok_or_else(|| CredentialError::InvalidStatus(format!("invalid {} {}", Self::SIZE_PROPERTY_NAME, size))),
+      Some(other) => Err(CredentialError::InvalidStatus(format!(
+        "expected {} to be a number, got {}",
+        Self::SIZE_PROPERTY_NAME,
+        other
+      ))),
+    }
+  }
+
+  //Returns the `statusMessage` table, empty when absent.
+  pub fn messages(&self) -> Result<Vec<StatusMessage>>
+  {
+    match self.0.properties.get(Self::MESSAGE_PROPERTY_NAME)
+    {
+      None => Ok(Vec::new()),
+      Some(Value::Array(messages)) => messages.iter().map(StatusMessage::from_value).collect(),
+      Some(other) => Err(CredentialError::InvalidStatus(format!(
+        "expected {} to be an array, got {}",
+        Self::MESSAGE_PROPERTY_NAME,
+        other
+      ))),
+    }
+  }
+}
+
+
+
+impl TryFrom<Status> for BitstringStatusListEntry
+{
+  type Error = CredentialError;
+
+  fn try_from(status: Status) -> Result<Self>
+  {
+    if status.type_ != Self::TYPE
+    {
+      return Err(CredentialError::InvalidStatus(format!(
+        "expected type '{}', got '{}'",
+        Self::TYPE,
+        status.type_
+      )));
+    }
+
+    for property in [Self::PURPOSE_PROPERTY_NAME, Self::INDEX_PROPERTY_NAME, Self::CREDENTIAL_PROPERTY_NAME]
+    {
+      if !status.properties.contains_key(property)
+      {
+        return Err(CredentialError::InvalidStatus(format!(
+          "missing required property '{}'",
+          property
+        )));
+      }
+    }
+
+    let list_credential = status_list_credential(&status.properties, Self::CREDENTIAL_PROPERTY_NAME)?;
+    let entry = Self(status, list_credential);
+    entry.purpose()?;
+    entry.index()?;
+    check_messages(entry.status_size()?, &entry.messages()?)?;
+    Ok(entry)
+  }
+}
+
+impl From<BitstringStatusListEntry> for Status
+{
+  fn from(entry: BitstringStatusListEntry) -> Self
+  {
+    entry.0
+  }
+}
@@ -0,0 +1,136 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::str::FromStr;
+
+use identity_core::common::Object;
+use identity_core::common::Url;
+use identity_core::common::Value;
+use identity_core::convert::FromJson;
+use identity_credential::credential::Credential;
+use identity_credential::credential::Status;
+
+use crate::CredentialError;
+use crate::Result;
+use crate::RevocationList2020Status::RevocationList2020Status;
+
+
+
+//Posizione di una credenziale all'interno di una RevocationList2020, estratta dal suo `credentialStatus`
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusReference
+{
+    status: RevocationList2020Status,
+    index: u64,
+}
+
+
+impl StatusReference
+{
+    fn new(status: Status) -> Result<Self>
+    {
+        let status = RevocationList2020Status::try_from(status)?;
+        let index = status.index()?;
+
+        Ok(StatusReference { status, index })
+    }
+
+    //Returns the typed status entry
+    pub fn status(&self) -> &RevocationList2020Status
+    {
+        &self.status
+    }
+
+    //Returns the `revocationListIndex` of the credential
+    pub fn index(&self) -> u64
+    {
+        self.index
+    }
+
+    //Returns the `revocationListCredential` the index refers to
+    pub fn list_credential(&self) -> &Url
+    {
+        self.status.list_credential()
+    }
+}
+
+
+
+//Reads the index property of a status entry. The specs encode it as a numeric string,
+//but issuers in the wild also use a plain JSON number: both are accepted.
+pub(crate) fn status_index(properties: &Object, name: &str) -> Result<u64>
+{
+    match properties.get(name)
+    {
+        Some(Value::String(index)) => u64::from_str(index).map_err(|err|
+        {
+            CredentialError::InvalidStatus(format!("expected {} to be an unsigned 64-bit integer: {}", name, err))
+        }),
+        Some(Value::Number(index)) => index.as_u64().ok_or_else(||
+        {
+            CredentialError::InvalidStatus(format!("expected {} to be an unsigned 64-bit integer, got {}", name, index))
+        }),
+        _ => Err(CredentialError::InvalidStatus(format!(
+            "expected {} to be an unsigned 64-bit integer expressed as a string or a number",
+            name
+        ))),
+    }
+}
+
+
+//Reads the property of a status entry holding the location of the list credential
+pub(crate) fn status_list_credential(properties: &Object, name: &str) -> Result<Url>
+{
+    match properties.get(name)
+    {
+        Some(Value::String(url)) => Url::parse(url).map_err(|err|
+        {
+            CredentialError::InvalidStatus(format!("expected {} to be a URL: {}", name, err))
+        }),
+        _ => Err(CredentialError::InvalidStatus(format!("expected {} to be a URL expressed as a string", name))),
+    }
+}
+
+
+//Returns the RevocationList2020 status of `credential`, failing if the status is absent or malformed
+pub fn status_reference<T>(credential: &Credential<T>) -> Result<StatusReference>
+{
+    match &credential.credential_status
+    {
+        Some(status) => StatusReference::new(status.clone()),
+        None => Err(CredentialError::MissingStatus),
+    }
+}
+
+
+//Returns every RevocationList2020 status of a credential given as JSON, where `credentialStatus`
+//may also be an array of entries. Entries of other types (e.g. StatusList2021Entry) are skipped,
+//malformed RevocationList2020 entries are an error.
+pub fn status_references(credential: &Value) -> Result<Vec<StatusReference>>
+{
+    let entries: Vec<&Value> = match credential.get("credentialStatus")
+    {
+        Some(Value::Array(entries)) => entries.iter().collect(),
+        Some(entry) => vec![entry],
+        None => return Err(CredentialError::MissingStatus),
+    };
+
+    let mut references = Vec::new();
+    for entry in entries
+    {
+        if entry.get("type").and_then(Value::as_str) != Some(RevocationList2020Status::TYPE)
+        {
+            continue;
+        }
+
+        let status = Status::from_json_value(entry.clone())
+            .map_err(|e| CredentialError::InvalidStatus(e.to_string()))?;
+        references.push(StatusReference::new(status)?);
+    }
+
+    if references.is_empty()
+    {
+        return Err(CredentialError::MissingStatus);
+    }
+    Ok(references)
+}
@@ -0,0 +1,199 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::io::Read;
+use std::io::Write;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use identity_credential::credential::Credential;
+
+use crate::CodingStage;
+use crate::CredentialError;
+use crate::Result;
+
+
+
+//Formato dei payload pubblicati sul ledger:
+//
+//  | version (1) | content type (1) | compression (1) | length (4, big endian) | body (length) |
+//
+//`length` è la lunghezza del body così come è scritto, cioè dopo l'eventuale compressione.
+//Un payload con byte in più o in meno rispetto a `length` viene rifiutato.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+//Size of the header preceding the body
+pub const ENVELOPE_HEADER_LEN: usize = 7;
+
+//Upper bound of a decompressed body, so a small payload cannot expand without limit
+pub const MAX_BODY_LEN: usize = 16 * 1024 * 1024;
+
+
+
+//What the body of an envelope contains
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentType
+{
+    //A list credential serialized as JSON
+    CredentialJson = 1,
+}
+
+impl ContentType
+{
+    fn to_byte(self) -> u8
+    {
+        self as u8
+    }
+
+    fn from_byte(byte: u8) -> Result<Self>
+    {
+        match byte
+        {
+            1 => Ok(ContentType::CredentialJson),
+            other => Err(CredentialError::InvalidEnvelope(format!("unknown content type {}", other))),
+        }
+    }
+}
+
+
+//How the body of an envelope is compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression
+{
+    #[default]
+    None = 0,
+    Gzip = 1,
+}
+
+impl Compression
+{
+    fn to_byte(self) -> u8
+    {
+        self as u8
+    }
+
+    fn from_byte(byte: u8) -> Result<Self>
+    {
+        match byte
+        {
+            0 => Ok(Compression::None),
+            1 => Ok(Compression::Gzip),
+            other => Err(CredentialError::InvalidEnvelope(format!("unknown compression {}", other))),
+        }
+    }
+}
+
+
+//A decoded envelope, with the body already decompressed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope
+{
+    pub content_type: ContentType,
+    pub compression: Compression,
+    pub body: Vec<u8>,
+}
+
+
+
+//Frames `body` into an envelope, compressing it first if requested
+pub fn encode_envelope(content_type: ContentType, compression: Compression, body: &[u8]) -> Result<Vec<u8>>
+{
+    if body.len() > MAX_BODY_LEN
+    {
+        return Err(CredentialError::InvalidEnvelope(format!("body of {} bytes exceeds {} bytes", body.len(), MAX_BODY_LEN)));
+    }
+
+    let stored = match compression
+    {
+        Compression::None => body.to_vec(),
+        Compression::Gzip =>
+        {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(body).map_err(|e| CredentialError::encoding(CodingStage::Gzip, e))?;
+            encoder.finish().map_err(|e| CredentialError::encoding(CodingStage::Gzip, e))?
+        }
+    };
+
+    let length = u32::try_from(stored.len())
+        .map_err(|_| CredentialError::InvalidEnvelope(format!("body of {} bytes is too long", stored.len())))?;
+
+    let mut bytes = Vec::with_capacity(ENVELOPE_HEADER_LEN + stored.len());
+    bytes.push(ENVELOPE_VERSION);
+    bytes.push(content_type.to_byte());
+    bytes.push(compression.to_byte());
+    bytes.extend_from_slice(&length.to_be_bytes());
+    bytes.extend_from_slice(&stored);
+    Ok(bytes)
+}
+
+
+//Parses an envelope, rejecting unknown versions or fields, a length that does not match the payload
+//and bodies that fail to decompress
+pub fn decode_envelope(bytes: &[u8]) -> Result<Envelope>
+{
+    if bytes.len() < ENVELOPE_HEADER_LEN
+    {
+        return Err(CredentialError::InvalidEnvelope(format!("payload of {} bytes is shorter than the header", bytes.len())));
+    }
+
+    if bytes[0] != ENVELOPE_VERSION
+    {
+        return Err(CredentialError::InvalidEnvelope(format!("unsupported version {}", bytes[0])));
+    }
+    let content_type = ContentType::from_byte(bytes[1])?;
+    let compression = Compression::from_byte(bytes[2])?;
+
+    let length = u32::from_be_bytes([bytes[3], bytes[4], bytes[5], bytes[6]]) as u64;
+    let stored = &bytes[ENVELOPE_HEADER_LEN..];
+    if stored.len() as u64 != length
+    {
+        return Err(CredentialError::InvalidEnvelope(format!(
+            "declared length {} does not match body of {} bytes",
+            length,
+            stored.len()
+        )));
+    }
+
+    let body = match compression
+    {
+        Compression::None => stored.to_vec(),
+        Compression::Gzip =>
+        {
+            //Un byte oltre il limite basta per riconoscere un body troppo grande
+            let mut body = Vec::new();
+            GzDecoder::new(stored)
+                .take(MAX_BODY_LEN as u64 + 1)
+                .read_to_end(&mut body)
+                .map_err(|e| CredentialError::decoding(CodingStage::Gzip, e))?;
+            if body.len() > MAX_BODY_LEN
+            {
+                return Err(CredentialError::InvalidEnvelope(format!("decompressed body exceeds {} bytes", MAX_BODY_LEN)));
+            }
+            body
+        }
+    };
+
+    Ok(Envelope { content_type, compression, body })
+}
+
+
+
+//Serializes a list credential and frames it for publication
+pub fn encode_credential(credential: &Credential, compression: Compression) -> Result<Vec<u8>>
+{
+    let json = credential.to_json_vec().map_err(|e| CredentialError::InvalidListCredential(e.to_string()))?;
+    encode_envelope(ContentType::CredentialJson, compression, &json)
+}
+
+//Parses a published payload back into the list credential it frames
+pub fn decode_credential(bytes: &[u8]) -> Result<Credential>
+{
+    let envelope = decode_envelope(bytes)?;
+    match envelope.content_type
+    {
+        ContentType::CredentialJson => Credential::from_json_slice(&envelope.body)
+            .map_err(|e| CredentialError::InvalidListCredential(e.to_string())),
+    }
+}
@@ -0,0 +1,325 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::capacity_to_u64;
+use crate::CredentialError;
+use crate::Result;
+use crate::RevocationList2020;
+
+
+
+//Come vengono scelti gli indici da assegnare.
+//
+//Sequential: 0, 1, 2, ... La posizione nella lista rivela l'ordine di emissione, e credenziali con indici
+//vicini sono state emesse in momenti vicini, quindi un verificatore può stimare quando e insieme a chi una
+//credenziale è stata emessa. Adatto solo quando questo non è un problema (liste interne, test).
+//
+//Random: ogni indice libero ha la stessa probabilità di essere scelto, estratto da un CSPRNG (ChaCha).
+//La posizione non dice nulla sull'ordine di emissione né sulle altre credenziali, e ogni holder si
+//confonde con tutti gli altri della lista (herd privacy): la protezione cresce con il numero di indici
+//assegnati, quindi una lista quasi vuota protegge poco. L'indice resta comunque stabile per tutta la vita
+//della credenziale, e chi osserva lo stesso indice in due presentazioni può collegarle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AllocationStrategy
+{
+    #[default]
+    Sequential,
+    Random,
+}
+
+
+//Oltre questi tentativi a vuoto l'indice casuale viene scelto tra quelli liberi
+const RANDOM_ATTEMPTS: usize = 64;
+
+//Parole per blocco: ogni blocco conta i propri indici assegnati, così il k-esimo indice libero
+//si trova scorrendo i blocchi e poi le parole di un solo blocco
+const WORDS_PER_BLOCK: usize = 64;
+const BLOCK_BITS: u64 = WORDS_PER_BLOCK as u64 * 64;
+
+
+//Hands out the indices of a revocation list to new credentials, never the same index twice.
+//Allocations are kept in memory, one bit per index: they are persisted with the rest of the list
+//by saving its `ListRecord` to an `IssuerStore`, which must happen before the index is handed out.
+//`ListManager::allocate` saves the record before returning the index.
+#[derive(Debug, Clone)]
+pub struct IndexAllocator
+{
+    list_id: String,
+    capacity: u64,
+
+    //Bit `i % 64` della parola `i / 64` a 1 se l'indice `i` è assegnato.
+    //I bit oltre `capacity` dell'ultima parola sono sempre a 0.
+    words: Vec<u64>,
+    allocated: u64,
+
+    //Indici assegnati in ciascun blocco di WORDS_PER_BLOCK parole
+    blocks: Vec<u32>,
+
+    //Primo indice che potrebbe essere libero
+    cursor: u64,
+
+    strategy: AllocationStrategy,
+    rng: StdRng,
+}
+
+
+impl IndexAllocator
+{
+    //Allocator for `list`, with no index allocated
+    pub fn new(list: &RevocationList2020) -> Self
+    {
+        Self::with_capacity(list.id(), capacity_to_u64(list.capacity()))
+    }
+
+    //Allocator for a list of `capacity` entries, with no index allocated
+    pub fn with_capacity(list_id: &str, capacity: u64) -> Self
+    {
+        IndexAllocator
+        {
+            list_id: list_id.to_owned(),
+            capacity,
+            words: vec![0; capacity.div_ceil(64) as usize],
+            allocated: 0,
+            blocks: vec![0; capacity.div_ceil(BLOCK_BITS) as usize],
+            cursor: 0,
+            strategy: AllocationStrategy::default(),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    //Allocator for `list` with the indices of `ranges` already allocated, e.g. as returned by `allocated_ranges()`.
+    //Ranges are closed, `[start, end]`, and empty when `start > end`. Fails if a range ends past the
+    //capacity or overlaps another one.
+    pub fn from_ranges(list: &RevocationList2020, ranges: &[(u64, u64)]) -> Result<Self>
+    {
+        let mut allocator = Self::new(list);
+        for &(start, end) in ranges.iter().filter(|(start, end)| start <= end)
+        {
+            if end >= allocator.capacity
+            {
+                return Err(CredentialError::IndexOutOfRange { index: end, capacity: allocator.capacity });
+            }
+
+            //Una parola alla volta: la parte del range che cade nella parola `position`
+            for position in start / 64..=end / 64
+            {
+                let low = if position == start / 64 { start % 64 } else { 0 };
+                let high = if position == end / 64 { end % 64 } else { 63 };
+                let mask = (u64::MAX >> (63 - high)) & (u64::MAX << low);
+
+                let word = &mut allocator.words[position as usize];
+                if *word & mask != 0
+                {
+                    return Err(CredentialError::IndexAlreadyAllocated(position * 64 + u64::from((*word & mask).trailing_zeros())));
+                }
+                *word |= mask;
+                allocator.blocks[(position * 64 / BLOCK_BITS) as usize] += mask.count_ones();
+                allocator.allocated += u64::from(mask.count_ones());
+            }
+        }
+        allocator.advance();
+        Ok(allocator)
+    }
+
+
+    //Sets how `allocate()` picks the next index
+    pub fn with_strategy(mut self, strategy: AllocationStrategy) -> Self
+    {
+        self.strategy = strategy;
+        self
+    }
+
+    //Seeds the random generator, so tests get a reproducible sequence. Never use it in production.
+    pub fn with_seed(mut self, seed: u64) -> Self
+    {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn strategy(&self) -> AllocationStrategy
+    {
+        self.strategy
+    }
+
+    pub fn list_id(&self) -> &str
+    {
+        &self.list_id
+    }
+
+    pub fn capacity(&self) -> u64
+    {
+        self.capacity
+    }
+
+    //Returns the number of allocated indices
+    pub fn allocated(&self) -> u64
+    {
+        self.allocated
+    }
+
+    //Returns the number of indices still available
+    pub fn remaining(&self) -> u64
+    {
+        self.capacity - self.allocated
+    }
+
+    //true when every index is allocated and new credentials need a new list
+    pub fn is_full(&self) -> bool
+    {
+        self.remaining() == 0
+    }
+
+    pub fn is_allocated(&self, index: u64) -> bool
+    {
+        index < self.capacity && self.words[(index / 64) as usize] & (1 << (index % 64)) != 0
+    }
+
+
+    //Allocates an unused index according to the strategy, or fails with `ListFull` when a new list must be opened
+    pub fn allocate(&mut self) -> Result<u64>
+    {
+        if self.is_full()
+        {
+            return Err(CredentialError::ListFull { list_id: self.list_id.clone(), capacity: self.capacity });
+        }
+
+        let index = match self.strategy
+        {
+            AllocationStrategy::Sequential => self.cursor,
+            AllocationStrategy::Random => self.random_free(),
+        };
+        self.allocate_index(index)?;
+        Ok(index)
+    }
+
+    //Estrazione uniforme tra gli indici liberi: per tentativi finché la lista è poco piena,
+    //altrimenti scegliendo direttamente il k-esimo indice libero
+    fn random_free(&mut self) -> u64
+    {
+        for _ in 0..RANDOM_ATTEMPTS
+        {
+            let index = self.rng.gen_range(0..self.capacity);
+            if !self.is_allocated(index)
+            {
+                return index;
+            }
+        }
+
+        let k = self.rng.gen_range(0..self.remaining());
+        self.select_free(k)
+    }
+
+    //Restituisce il k-esimo indice libero (da 0), con k < remaining()
+    fn select_free(&self, mut k: u64) -> u64
+    {
+        let mut block = 0;
+        loop
+        {
+            let free = self.span(block as u64 * BLOCK_BITS, BLOCK_BITS) - u64::from(self.blocks[block]);
+            if k < free
+            {
+                break;
+            }
+            k -= free;
+            block += 1;
+        }
+
+        let mut position = block * WORDS_PER_BLOCK;
+        loop
+        {
+            let word = self.words[position];
+            let free = self.span(position as u64 * 64, 64) - u64::from(word.count_ones());
+            if k < free
+            {
+                //Azzero i primi k bit liberi: il successivo è quello cercato
+                let mut zeros = !word;
+                for _ in 0..k
+                {
+                    zeros &= zeros - 1;
+                }
+                return position as u64 * 64 + u64::from(zeros.trailing_zeros());
+            }
+            k -= free;
+            position += 1;
+        }
+    }
+
+    //Numero di indici della lista tra `start` e `start + len`
+    fn span(&self, start: u64, len: u64) -> u64
+    {
+        self.capacity.min(start + len) - start
+    }
+
+    //Allocates a specific index, failing if it is out of range or already allocated
+    pub fn allocate_index(&mut self, index: u64) -> Result<()>
+    {
+        if index >= self.capacity
+        {
+            return Err(CredentialError::IndexOutOfRange { index, capacity: self.capacity });
+        }
+        if self.is_allocated(index)
+        {
+            return Err(CredentialError::IndexAlreadyAllocated(index));
+        }
+
+        self.words[(index / 64) as usize] |= 1 << (index % 64);
+        self.blocks[(index / BLOCK_BITS) as usize] += 1;
+        self.allocated += 1;
+        self.advance();
+        Ok(())
+    }
+
+
+    //Annulla l'assegnazione di un indice non ancora consegnato, ad esempio perché il salvataggio è fallito
+    pub(crate) fn release(&mut self, index: u64)
+    {
+        if self.is_allocated(index)
+        {
+            self.words[(index / 64) as usize] &= !(1 << (index % 64));
+            self.blocks[(index / BLOCK_BITS) as usize] -= 1;
+            self.allocated -= 1;
+            self.cursor = self.cursor.min(index);
+        }
+    }
+
+    //Porta il cursore al primo indice libero, saltando le parole piene
+    fn advance(&mut self)
+    {
+        while self.cursor < self.capacity
+        {
+            let word = self.words[(self.cursor / 64) as usize] >> (self.cursor % 64);
+            if word & 1 == 0
+            {
+                return;
+            }
+            self.cursor += u64::from((!word).trailing_zeros());
+        }
+    }
+
+    //Returns the allocated indices as closed ranges `[start, end]`, in ascending order
+    pub fn allocated_ranges(&self) -> Vec<(u64, u64)>
+    {
+        let mut ranges: Vec<(u64, u64)> = Vec::new();
+        for (position, &word) in self.words.iter().enumerate()
+        {
+            let mut word = word;
+            while word != 0
+            {
+                let index = position as u64 * 64 + u64::from(word.trailing_zeros());
+                word &= word - 1;
+                match ranges.last_mut()
+                {
+                    Some((_, end)) if *end + 1 == index => *end = index,
+                    _ => ranges.push((index, index)),
+                }
+            }
+        }
+        ranges
+    }
+}
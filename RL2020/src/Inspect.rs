@@ -0,0 +1,133 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::fmt;
+
+use base64::{decode_config, STANDARD};
+use identity_core::common::Value;
+use identity_core::convert::FromJson;
+use identity_credential::credential::Credential;
+use serde_derive::Serialize;
+
+use crate::capacity_to_u64;
+use crate::BitOrder;
+use crate::CodingStage;
+use crate::CredentialError;
+use crate::Result;
+use crate::RevocationList2020;
+
+
+
+//Id usato per decodificare una encodedList senza la sua VC lista
+const UNNAMED_LIST: &str = "urn:rl2020:inspect";
+
+
+
+//What an `encodedList` contains, for looking at published lists by hand
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListReport
+{
+    //Id of the list credential, if the input was a credential
+    pub id: Option<String>,
+
+    //Size of the bitstring in kb and number of entries
+    pub size_kb: usize,
+    pub capacity: u64,
+
+    pub revoked: u64,
+
+    //Revoked indices as closed ranges [start, end]
+    pub ranges: Vec<(u64, u64)>,
+
+    //Length of the `encodedList` text, of the compressed bitstring and of the bitstring
+    pub encoded_len: usize,
+    pub compressed_len: usize,
+    pub bitstring_len: usize,
+
+    //Bitstring length over compressed length
+    pub compression_ratio: f64,
+}
+
+
+//Decodes an `encodedList` value, reading the bits of each byte in `order`
+pub fn inspect_encoded_list(encoded: &str, order: BitOrder) -> Result<ListReport>
+{
+    report(None, encoded.trim(), order)
+}
+
+//Decodes the `encodedList` of a list credential. The credential is not verified, and only needs
+//a subject with an `encodedList`, so malformed lists can be looked at too.
+pub fn inspect_list_credential(credential: &Credential, order: BitOrder) -> Result<ListReport>
+{
+    let encoded = credential
+        .credential_subject
+        .iter()
+        .find_map(|subject| match subject.properties.get("encodedList")
+        {
+            Some(Value::String(encoded)) => Some(encoded.as_str()),
+            _ => None,
+        })
+        .ok_or_else(|| CredentialError::InvalidListCredential("no subject with an encodedList".to_owned()))?;
+
+    report(credential.id.as_ref().map(|id| id.to_string()), encoded, order)
+}
+
+//Accepts either an `encodedList` value or a list credential JSON
+pub fn inspect(input: &str, order: BitOrder) -> Result<ListReport>
+{
+    let input = input.trim();
+    if input.starts_with('{')
+    {
+        let credential = Credential::from_json(input).map_err(|e| CredentialError::InvalidListCredential(e.to_string()))?;
+        return inspect_list_credential(&credential, order);
+    }
+    inspect_encoded_list(input, order)
+}
+
+
+fn report(id: Option<String>, encoded: &str, order: BitOrder) -> Result<ListReport>
+{
+    let list = RevocationList2020::new_withList(id.as_deref().unwrap_or(UNNAMED_LIST), encoded)?.with_bit_order(order);
+    let compressed_len = decode_config(encoded, STANDARD).map_err(|e| CredentialError::decoding(CodingStage::Base64, e))?.len();
+    let bitstring_len = list.list.bits().byte_len();
+
+    Ok(ListReport
+    {
+        id,
+        size_kb: list.size(),
+        capacity: capacity_to_u64(list.capacity()),
+        revoked: list.count_revoked(),
+        ranges: list.iter_set_ranges().collect(),
+        encoded_len: encoded.len(),
+        compressed_len,
+        bitstring_len,
+        compression_ratio: if compressed_len == 0 { 0.0 } else { bitstring_len as f64 / compressed_len as f64 },
+    })
+}
+
+
+impl fmt::Display for ListReport
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        if let Some(id) = &self.id
+        {
+            writeln!(f, "id:          {}", id)?;
+        }
+        writeln!(f, "size:        {} kb", self.size_kb)?;
+        writeln!(f, "capacity:    {} entries", self.capacity)?;
+        writeln!(f, "revoked:     {}", self.revoked)?;
+        writeln!(
+            f,
+            "compression: {} -> {} bytes ({} base64 chars), ratio {:.1}",
+            self.bitstring_len, self.compressed_len, self.encoded_len, self.compression_ratio
+        )?;
+
+        let ranges: Vec<String> = self
+            .ranges
+            .iter()
+            .map(|(start, end)| if start == end { start.to_string() } else { format!("{}-{}", start, end) })
+            .collect();
+        write!(f, "ranges:      {}", if ranges.is_empty() { "-".to_owned() } else { ranges.join(", ") })
+    }
+}
@@ -0,0 +1,462 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_credential::credential::Credential;
+use rusqlite::params;
+use rusqlite::Connection;
+use rusqlite::OptionalExtension;
+use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::BitOrder;
+use crate::CredentialError;
+use crate::IndexAllocator::AllocationStrategy;
+use crate::IndexAllocator::IndexAllocator;
+use crate::Result;
+use crate::RevocationList2020;
+use crate::RevocationList2020Credential::RevocationList2020Credential;
+
+
+
+//Versione del formato dei record salvati
+pub const RECORD_VERSION: u32 = 1;
+
+
+
+//Last version of a list published on the ledger
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublishedVersion
+{
+    pub issuance_date: Timestamp,
+    pub publication: String,
+}
+
+//I verificatori scelgono la versione con la issuance date più recente: una nuova versione pubblicata
+//nello stesso secondo della precedente deve comunque risultare successiva
+pub(crate) fn next_issuance_date(last: Option<&PublishedVersion>, date: Timestamp) -> Result<Timestamp>
+{
+    match last
+    {
+        Some(last) if date <= last.issuance_date => Timestamp::from_unix(last.issuance_date.to_unix() + 1)
+            .map_err(|e| CredentialError::Publish(format!("invalid issuance date: {}", e))),
+        _ => Ok(date),
+    }
+}
+
+
+//Everything the issuer must not lose about one of its lists: the bitstring, the allocated indices,
+//the credentials they were allocated to and the last published version
+#[derive(Debug, Clone)]
+pub struct ListRecord
+{
+    pub list: RevocationList2020,
+    pub allocator: IndexAllocator,
+
+    //indice -> id della credenziale a cui è stato assegnato
+    pub credentials: BTreeMap<u64, String>,
+
+    pub published: Option<PublishedVersion>,
+
+    //true se la lista è cambiata dopo l'ultima pubblicazione, o non è mai stata pubblicata
+    pub pending: bool,
+}
+
+impl ListRecord
+{
+    //Record of a list with no index allocated and never published
+    pub fn new(list: RevocationList2020) -> Self
+    {
+        let allocator = IndexAllocator::new(&list);
+        ListRecord { list, allocator, credentials: BTreeMap::new(), published: None, pending: true }
+    }
+
+    pub fn list_id(&self) -> &str
+    {
+        self.list.id()
+    }
+
+    //true if the list changed since it was last published
+    pub fn needs_publication(&self) -> bool
+    {
+        self.pending || self.list.is_dirty()
+    }
+
+    //Builds the unsigned list credential of the current bitstring, issued after the last published version.
+    //The record stays pending until `set_published` is called.
+    pub fn list_credential(&mut self, issuer: &Url) -> Result<Credential>
+    {
+        //encode() azzera lo stato dirty della lista: se la pubblicazione fallisce deve restare da pubblicare
+        self.pending = true;
+        self.list.encode()?;
+        let mut credential = RevocationList2020Credential::new(issuer.clone(), self.list.clone())?.into_credential();
+        credential.issuance_date = next_issuance_date(self.published.as_ref(), credential.issuance_date)?;
+        Ok(credential)
+    }
+
+    //Records `credential`, published as `publication`, as the last published version
+    pub fn set_published(&mut self, credential: &Credential, publication: &str)
+    {
+        self.published = Some(PublishedVersion { issuance_date: credential.issuance_date, publication: publication.to_owned() });
+        self.pending = false;
+    }
+}
+
+
+//Forma salvata di un ListRecord: la bitstring è salvata come encodedList, gli indici assegnati come intervalli
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredRecord
+{
+    version: u32,
+    list_id: String,
+    encoded_list: String,
+    lsb0: bool,
+    allocated: Vec<(u64, u64)>,
+    strategy: AllocationStrategy,
+
+    credentials: BTreeMap<u64, String>,
+    published: Option<PublishedVersion>,
+    pending: bool,
+}
+
+
+//Serializes a record as stored by every backend
+pub fn encode_record(record: &ListRecord) -> Result<Vec<u8>>
+{
+    let stored = StoredRecord
+    {
+        version: RECORD_VERSION,
+        list_id: record.list_id().to_owned(),
+        encoded_list: record.list.get_encList()?,
+        lsb0: record.list.bit_order() == BitOrder::Lsb0,
+        allocated: record.allocator.allocated_ranges(),
+        strategy: record.allocator.strategy(),
+        credentials: record.credentials.clone(),
+        published: record.published.clone(),
+        pending: record.needs_publication(),
+    };
+    serde_json::to_vec(&stored).map_err(|e| CredentialError::Storage(e.to_string()))
+}
+
+//Parses a stored record, rejecting unknown versions and allocations that do not fit the list
+pub fn decode_record(bytes: &[u8]) -> Result<ListRecord>
+{
+    let stored: StoredRecord = serde_json::from_slice(bytes).map_err(|e| CredentialError::Storage(e.to_string()))?;
+    if stored.version != RECORD_VERSION
+    {
+        return Err(CredentialError::Storage(format!("unsupported record version {}", stored.version)));
+    }
+    let invalid = |what: String| CredentialError::Storage(format!("record of list '{}': {}", stored.list_id, what));
+
+    let order = if stored.lsb0 { BitOrder::Lsb0 } else { BitOrder::Msb0 };
+    let list = RevocationList2020::new_withList(&stored.list_id, &stored.encoded_list)?.with_bit_order(order);
+
+    if let Some((start, end)) = stored.allocated.iter().find(|(start, end)| start > end)
+    {
+        return Err(invalid(format!("invalid range [{}, {}]", start, end)));
+    }
+    let allocator = IndexAllocator::from_ranges(&list, &stored.allocated)
+        .map_err(|e| invalid(e.to_string()))?
+        .with_strategy(stored.strategy);
+
+    if let Some(index) = stored.credentials.keys().find(|index| !allocator.is_allocated(**index))
+    {
+        return Err(invalid(format!("credential at index {} was never allocated", index)));
+    }
+
+    Ok(ListRecord
+    {
+        list,
+        allocator,
+        credentials: stored.credentials,
+        published: stored.published,
+        pending: stored.pending,
+    })
+}
+
+
+
+//Persists the state of the issuer lists.
+//`save` replaces the record atomically and returns only once it is durable: after a crash `load` returns
+//either the previous or the new record, never a mix of the two, so a saved revocation is never lost.
+pub trait IssuerStore
+{
+    fn save(&self, record: &ListRecord) -> Result<()>;
+
+    //Saves several records. Backends with transactions write all of them or none.
+    fn save_all(&self, records: &[ListRecord]) -> Result<()>
+    {
+        records.iter().try_for_each(|record| self.save(record))
+    }
+
+    fn load(&self, list_id: &str) -> Result<Option<ListRecord>>;
+
+    //Returns the ids of the stored lists, in ascending order
+    fn list_ids(&self) -> Result<Vec<String>>;
+
+    //Removes a record, returning false if it did not exist
+    fn remove(&self, list_id: &str) -> Result<bool>;
+}
+
+
+
+//Records stored as files in a directory, one per list.
+//A record is written to a temporary file, flushed and renamed over the previous one.
+#[derive(Debug, Clone)]
+pub struct FileStore
+{
+    root: PathBuf,
+}
+
+impl FileStore
+{
+    const EXTENSION: &'static str = "json";
+
+    pub fn new(root: impl Into<PathBuf>) -> Self
+    {
+        FileStore { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path
+    {
+        &self.root
+    }
+
+    //L'id della lista è un URL di lunghezza qualsiasi: il nome del file è il suo SHA-256 in esadecimale,
+    //l'id vero è salvato nel record
+    fn record_path(&self, list_id: &str) -> PathBuf
+    {
+        let name: String = Sha256::digest(list_id.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+        self.root.join(name).with_extension(Self::EXTENSION)
+    }
+
+    fn read(&self, path: &Path) -> Result<Option<Vec<u8>>>
+    {
+        match fs::read(path)
+        {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(CredentialError::Storage(format!("reading {}: {}", path.display(), e))),
+        }
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<bool>
+    {
+        match fs::remove_file(path)
+        {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(CredentialError::Storage(format!("removing {}: {}", path.display(), e))),
+        }
+    }
+
+    //Rende persistente la creazione, la rinomina o la rimozione di un file nella directory.
+    //Non tutte le piattaforme permettono di aprire una directory: lì basta la rinomina.
+    fn sync_root(&self)
+    {
+        if let Ok(dir) = fs::File::open(&self.root)
+        {
+            let _ = dir.sync_all();
+        }
+    }
+}
+
+impl IssuerStore for FileStore
+{
+    fn save(&self, record: &ListRecord) -> Result<()>
+    {
+        let path = self.record_path(record.list_id());
+        let err = |e: std::io::Error| CredentialError::Storage(format!("writing {}: {}", path.display(), e));
+        let bytes = encode_record(record)?;
+        fs::create_dir_all(&self.root).map_err(err)?;
+
+        static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+        let tmp = self.root.join(format!(".{}-{}.tmp", std::process::id(), TMP_COUNTER.fetch_add(1, Ordering::Relaxed)));
+        let written = fs::File::create(&tmp)
+            .and_then(|mut file| file.write_all(&bytes).and_then(|_| file.sync_all()))
+            .and_then(|_| fs::rename(&tmp, &path));
+        if let Err(e) = written
+        {
+            let _ = fs::remove_file(&tmp);
+            return Err(err(e));
+        }
+
+        self.sync_root();
+        Ok(())
+    }
+
+    fn load(&self, list_id: &str) -> Result<Option<ListRecord>>
+    {
+        let bytes = match self.read(&self.record_path(list_id))?
+        {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+
+        let record = decode_record(&bytes)?;
+        if record.list_id() != list_id
+        {
+            return Err(CredentialError::Storage(format!("record of list '{}' found for list '{}'", record.list_id(), list_id)));
+        }
+        Ok(Some(record))
+    }
+
+    fn list_ids(&self) -> Result<Vec<String>>
+    {
+        let err = |e: std::io::Error| CredentialError::Storage(format!("reading {}: {}", self.root.display(), e));
+        let entries = match fs::read_dir(&self.root)
+        {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(err(e)),
+        };
+
+        //Il nome del file non contiene l'id: lo leggo dal record
+        #[derive(Deserialize)]
+        struct StoredId
+        {
+            list_id: String,
+        }
+
+        let mut ids = Vec::new();
+        for entry in entries
+        {
+            let path = entry.map_err(err)?.path();
+            if path.extension().is_none_or(|extension| extension != Self::EXTENSION)
+            {
+                continue;
+            }
+            let bytes = fs::read(&path).map_err(|e| CredentialError::Storage(format!("reading {}: {}", path.display(), e)))?;
+            let stored: StoredId = serde_json::from_slice(&bytes).map_err(|e| CredentialError::Storage(format!("{}: {}", path.display(), e)))?;
+            ids.push(stored.list_id);
+        }
+        ids.sort();
+        ids.dedup();
+        Ok(ids)
+    }
+
+    fn remove(&self, list_id: &str) -> Result<bool>
+    {
+        let removed = self.remove_file(&self.record_path(list_id))?;
+        if removed
+        {
+            self.sync_root();
+        }
+        Ok(removed)
+    }
+}
+
+
+
+//Records stored in a SQLite database, one row per list.
+//The database runs in WAL mode with full synchronization, so a committed transaction survives a crash.
+pub struct SqliteStore
+{
+    connection: Mutex<Connection>,
+}
+
+fn sqlite_error(e: rusqlite::Error) -> CredentialError
+{
+    CredentialError::Storage(e.to_string())
+}
+
+impl SqliteStore
+{
+    //Opens the database at `path`, creating it if it does not exist
+    pub fn open(path: impl AsRef<Path>) -> Result<Self>
+    {
+        Self::init(Connection::open(path).map_err(sqlite_error)?)
+    }
+
+    //Database that lives as long as the store, for tests
+    pub fn open_in_memory() -> Result<Self>
+    {
+        Self::init(Connection::open_in_memory().map_err(sqlite_error)?)
+    }
+
+    fn init(connection: Connection) -> Result<Self>
+    {
+        connection
+            .pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+            .map_err(sqlite_error)?;
+        connection.pragma_update(None, "synchronous", "FULL").map_err(sqlite_error)?;
+        connection
+            .execute_batch("CREATE TABLE IF NOT EXISTS lists (list_id TEXT PRIMARY KEY NOT NULL, record BLOB NOT NULL)")
+            .map_err(sqlite_error)?;
+        Ok(SqliteStore { connection: Mutex::new(connection) })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Connection>
+    {
+        //Le transazioni non concluse vengono annullate da SQLite, quindi un lock avvelenato resta utilizzabile
+        self.connection.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl IssuerStore for SqliteStore
+{
+    fn save(&self, record: &ListRecord) -> Result<()>
+    {
+        self.save_all(std::slice::from_ref(record))
+    }
+
+    fn save_all(&self, records: &[ListRecord]) -> Result<()>
+    {
+        let encoded = records
+            .iter()
+            .map(|record| Ok((record.list_id(), encode_record(record)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut connection = self.lock();
+        let transaction = connection.transaction().map_err(sqlite_error)?;
+        for (list_id, bytes) in &encoded
+        {
+            transaction
+                .execute(
+                    "INSERT INTO lists (list_id, record) VALUES (?1, ?2) ON CONFLICT(list_id) DO UPDATE SET record = excluded.record",
+                    params![list_id, bytes],
+                )
+                .map_err(sqlite_error)?;
+        }
+        transaction.commit().map_err(sqlite_error)
+    }
+
+    fn load(&self, list_id: &str) -> Result<Option<ListRecord>>
+    {
+        let bytes: Option<Vec<u8>> = self
+            .lock()
+            .query_row("SELECT record FROM lists WHERE list_id = ?1", params![list_id], |row| row.get(0))
+            .optional()
+            .map_err(sqlite_error)?;
+        bytes.map(|bytes| decode_record(&bytes)).transpose()
+    }
+
+    fn list_ids(&self) -> Result<Vec<String>>
+    {
+        let connection = self.lock();
+        let mut statement = connection.prepare("SELECT list_id FROM lists ORDER BY list_id").map_err(sqlite_error)?;
+        let ids = statement
+            .query_map([], |row| row.get(0))
+            .map_err(sqlite_error)?
+            .collect::<rusqlite::Result<Vec<String>>>()
+            .map_err(sqlite_error)?;
+        Ok(ids)
+    }
+
+    fn remove(&self, list_id: &str) -> Result<bool>
+    {
+        let removed = self.lock().execute("DELETE FROM lists WHERE list_id = ?1", params![list_id]).map_err(sqlite_error)?;
+        Ok(removed > 0)
+    }
+}
@@ -0,0 +1,355 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use bee_message::prelude::MessageId;
+use bee_message::prelude::Payload;
+use iota_client::Client;
+use sha2::{Digest, Sha256};
+
+use crate::CredentialError;
+use crate::Result;
+
+
+
+//Un messaggio pubblicato sotto un indice: l'id assegnato dal ledger e il payload così come è stato scritto
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Publication
+{
+    pub id: String,
+    pub payload: Vec<u8>,
+}
+
+
+//Issuer side: writes a new version of a status list credential under an index
+#[async_trait]
+pub trait StatusListPublisher
+{
+    //Publishes `payload` under `index`, returning the id of the new publication
+    async fn publish(&self, index: &str, payload: &[u8]) -> Result<String>;
+}
+
+//Verifier side: reads every version of a status list credential published under an index
+#[async_trait]
+pub trait StatusListSource
+{
+    //Returns all publications under `index`, an empty vector if there is none
+    async fn fetch(&self, index: &str) -> Result<Vec<Publication>>;
+
+    //Returns the ids of the publications under `index`, without their payloads, so a verifier can tell
+    //whether anything new was published. Sources able to list them more cheaply than `fetch` override it.
+    async fn publication_ids(&self, index: &str) -> Result<Vec<String>>
+    {
+        Ok(self.fetch(index).await?.into_iter().map(|publication| publication.id).collect())
+    }
+}
+
+
+
+//IOTA
+
+//Publishes the list credentials as indexation payloads on an IOTA node
+pub struct IotaIndexation
+{
+    client: Client,
+}
+
+impl IotaIndexation
+{
+    pub fn new(client: Client) -> Self
+    {
+        IotaIndexation { client }
+    }
+
+    //Connects to the node at `url`
+    pub async fn with_node(url: &str) -> Result<Self>
+    {
+        let client = Client::builder()
+            .with_node(url)
+            .map_err(|e| CredentialError::Publish(e.to_string()))?
+            .finish()
+            .await
+            .map_err(|e| CredentialError::Publish(e.to_string()))?;
+
+        Ok(IotaIndexation { client })
+    }
+
+    pub fn client(&self) -> &Client
+    {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl StatusListPublisher for IotaIndexation
+{
+    async fn publish(&self, index: &str, payload: &[u8]) -> Result<String>
+    {
+        let message = self.client
+            .message()
+            .with_index(index)
+            .with_data(payload.to_vec())
+            .finish()
+            .await
+            .map_err(|e| CredentialError::Publish(e.to_string()))?;
+
+        Ok(message.id().0.to_string())
+    }
+}
+
+#[async_trait]
+impl StatusListSource for IotaIndexation
+{
+    async fn fetch(&self, index: &str) -> Result<Vec<Publication>>
+    {
+        let ids = self.client
+            .get_message()
+            .index(index)
+            .await
+            .map_err(|e| CredentialError::Fetch(e.to_string()))?;
+
+        let mut publications = Vec::new();
+        for id in ids.iter()
+        {
+            let message = self.client
+                .get_message()
+                .data(id)
+                .await
+                .map_err(|e| CredentialError::Fetch(e.to_string()))?;
+
+            //Solo i dati dell'indexation payload, senza l'intestazione del messaggio
+            if let Some(Payload::Indexation(indexation)) = message.payload()
+            {
+                publications.push(Publication { id: id.to_string(), payload: indexation.data().to_vec() });
+            }
+        }
+
+        Ok(publications)
+    }
+
+    //Una sola richiesta al nodo, senza scaricare i messaggi
+    async fn publication_ids(&self, index: &str) -> Result<Vec<String>>
+    {
+        let ids = self.client
+            .get_message()
+            .index(index)
+            .await
+            .map_err(|e| CredentialError::Fetch(e.to_string()))?;
+
+        Ok(ids.iter().map(|id| id.to_string()).collect())
+    }
+}
+
+
+
+//In memoria
+
+//Ledger kept in memory, for tests and local runs. Clones share the same publications.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryLedger
+{
+    messages: Arc<Mutex<BTreeMap<String, Vec<Publication>>>>,
+}
+
+impl MemoryLedger
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl StatusListPublisher for MemoryLedger
+{
+    async fn publish(&self, index: &str, payload: &[u8]) -> Result<String>
+    {
+        let mut messages = self.messages.lock().map_err(|e| CredentialError::Publish(e.to_string()))?;
+        let published = messages.entry(index.to_owned()).or_default();
+
+        let id = format!("{}/{}", index, published.len());
+        published.push(Publication { id: id.clone(), payload: payload.to_vec() });
+        Ok(id)
+    }
+}
+
+#[async_trait]
+impl StatusListSource for MemoryLedger
+{
+    async fn fetch(&self, index: &str) -> Result<Vec<Publication>>
+    {
+        let messages = self.messages.lock().map_err(|e| CredentialError::Fetch(e.to_string()))?;
+        Ok(messages.get(index).cloned().unwrap_or_default())
+    }
+
+    async fn publication_ids(&self, index: &str) -> Result<Vec<String>>
+    {
+        let messages = self.messages.lock().map_err(|e| CredentialError::Fetch(e.to_string()))?;
+        Ok(messages.get(index).map(|published| published.iter().map(|p| p.id.clone()).collect()).unwrap_or_default())
+    }
+}
+
+
+
+//Filesystem
+
+//Ledger stored in a directory: one subdirectory per index, one file per publication.
+//Each file is written under a temporary name and then linked in place, so readers never see partial payloads
+//and concurrent publishers never overwrite each other.
+#[derive(Debug, Clone)]
+pub struct FileLedger
+{
+    root: PathBuf,
+}
+
+impl FileLedger
+{
+    const EXTENSION: &'static str = "msg";
+
+    pub fn new(root: impl Into<PathBuf>) -> Self
+    {
+        FileLedger { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path
+    {
+        &self.root
+    }
+
+    //L'indice può contenere qualsiasi carattere ed essere lungo quanto un URL: il nome della directory
+    //è lo SHA-256 dell'indice in esadecimale, come per i record di FileStore
+    fn index_dir(&self, index: &str) -> PathBuf
+    {
+        let name: String = Sha256::digest(index.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+        self.root.join(name)
+    }
+
+    fn sequence(path: &Path) -> Option<u64>
+    {
+        if path.extension()? != Self::EXTENSION
+        {
+            return None;
+        }
+        path.file_stem()?.to_str()?.parse().ok()
+    }
+
+    fn sequences(dir: &Path) -> std::io::Result<Vec<(u64, PathBuf)>>
+    {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)?
+        {
+            let path = entry?.path();
+            if let Some(seq) = Self::sequence(&path)
+            {
+                files.push((seq, path));
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    //Scrive il payload su un file temporaneo e lo collega al primo numero di sequenza libero.
+    //Il file temporaneo viene rimosso in ogni caso.
+    fn write_publication(dir: &Path, index: &str, payload: &[u8]) -> std::io::Result<String>
+    {
+        fs::create_dir_all(dir)?;
+
+        static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+        let tmp = dir.join(format!(".{}-{}.tmp", std::process::id(), TMP_COUNTER.fetch_add(1, Ordering::Relaxed)));
+        let result = fs::File::create(&tmp)
+            .and_then(|mut file| file.write_all(payload).and_then(|_| file.sync_all()))
+            .and_then(|_| Self::link_next(dir, index, &tmp));
+
+        let _ = fs::remove_file(&tmp);
+        result
+    }
+
+    fn link_next(dir: &Path, index: &str, tmp: &Path) -> std::io::Result<String>
+    {
+        let mut seq = Self::sequences(dir)?.last().map_or(0, |(seq, _)| seq + 1);
+        loop
+        {
+            let target = dir.join(format!("{:020}.{}", seq, Self::EXTENSION));
+            match fs::hard_link(tmp, &target)
+            {
+                Ok(()) => return Ok(format!("{}/{}", index, seq)),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => seq += 1,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn read_publications(dir: &Path, index: &str) -> std::io::Result<Vec<Publication>>
+    {
+        Self::existing_sequences(dir)?
+            .into_iter()
+            .map(|(seq, path)| Ok(Publication { id: format!("{}/{}", index, seq), payload: fs::read(path)? }))
+            .collect()
+    }
+
+    fn read_publication_ids(dir: &Path, index: &str) -> std::io::Result<Vec<String>>
+    {
+        Ok(Self::existing_sequences(dir)?.into_iter().map(|(seq, _)| format!("{}/{}", index, seq)).collect())
+    }
+
+    //Come `sequences`, senza errore se sotto l'indice non è mai stato pubblicato nulla
+    fn existing_sequences(dir: &Path) -> std::io::Result<Vec<(u64, PathBuf)>>
+    {
+        match Self::sequences(dir)
+        {
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+            result => result,
+        }
+    }
+}
+
+#[async_trait]
+impl StatusListPublisher for FileLedger
+{
+    //Le operazioni sul filesystem sono bloccanti: girano sul pool di thread di tokio, non sul runtime
+    async fn publish(&self, index: &str, payload: &[u8]) -> Result<String>
+    {
+        let dir = self.index_dir(index);
+        let (index, payload) = (index.to_owned(), payload.to_vec());
+        tokio::task::spawn_blocking(move || Self::write_publication(&dir, &index, &payload))
+            .await
+            .map_err(|e| CredentialError::Publish(e.to_string()))?
+            .map_err(|e| CredentialError::Publish(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl StatusListSource for FileLedger
+{
+    async fn fetch(&self, index: &str) -> Result<Vec<Publication>>
+    {
+        let dir = self.index_dir(index);
+        let index = index.to_owned();
+        tokio::task::spawn_blocking(move || Self::read_publications(&dir, &index))
+            .await
+            .map_err(|e| CredentialError::Fetch(e.to_string()))?
+            .map_err(|e| CredentialError::Fetch(e.to_string()))
+    }
+
+    //Solo i nomi dei file, senza leggerli
+    async fn publication_ids(&self, index: &str) -> Result<Vec<String>>
+    {
+        let dir = self.index_dir(index);
+        let index = index.to_owned();
+        tokio::task::spawn_blocking(move || Self::read_publication_ids(&dir, &index))
+            .await
+            .map_err(|e| CredentialError::Fetch(e.to_string()))?
+            .map_err(|e| CredentialError::Fetch(e.to_string()))
+    }
+}
@@ -0,0 +1,334 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::common::Value;
+use identity_credential::credential::Credential;
+use tokio::task::JoinHandle;
+//Il clock di tokio, che nei test si può fermare e far avanzare
+use tokio::time::Instant;
+
+use crate::BitOrder;
+use crate::CredentialError;
+use crate::CredentialStatus::status_reference;
+use crate::Ledger::StatusListSource;
+use crate::Result;
+use crate::RevocationList2020Credential::RevocationList2020Credential;
+use crate::Verifier::fetch_verified_list;
+use crate::Verifier::list_index;
+use crate::Verifier::read_status;
+use crate::Verifier::IssuerResolver;
+use crate::Verifier::ListVersion;
+use crate::Verifier::StatusVerdict;
+
+
+
+//Valori predefiniti della cache
+pub const DEFAULT_TTL: Duration = Duration::from_secs(300);
+pub const DEFAULT_MAX_ENTRIES: usize = 1024;
+pub const DEFAULT_REFRESH_AHEAD: Duration = Duration::from_secs(30);
+
+
+
+//Lista verificata in cache, con quanto serve per riscaricarla in background
+#[derive(Clone)]
+struct Entry
+{
+    list: Arc<RevocationList2020Credential>,
+    index: String,
+    issuer: String,
+
+    //Id ordinati delle pubblicazioni da cui è stata scelta la lista: se non cambiano non serve riscaricarle
+    seen: Vec<String>,
+    expires_at: Instant,
+    last_used: Instant,
+}
+
+
+//Cache of verified lists keyed by list credential id, for verifiers checking many credentials.
+//An entry lives for the TTL, or less if the list credential expires (`expirationDate`/`validUntil`) earlier;
+//at most `max_entries` lists are kept, evicting the least recently used one.
+//An expired entry is renewed without downloading and verifying the list again when nothing new was published.
+pub struct ListCache<R, S>
+{
+    resolver: R,
+    source: S,
+    ttl: Duration,
+    max_entries: usize,
+    refresh_ahead: Duration,
+    order: BitOrder,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+
+impl<R, S> ListCache<R, S>
+where
+    R: IssuerResolver + Send + Sync,
+    S: StatusListSource + Send + Sync,
+{
+    pub fn new(resolver: R, source: S) -> Self
+    {
+        ListCache
+        {
+            resolver,
+            source,
+            ttl: DEFAULT_TTL,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            refresh_ahead: DEFAULT_REFRESH_AHEAD,
+            order: BitOrder::default(),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    //Sets how long a list is used before being fetched again
+    pub fn with_ttl(mut self, ttl: Duration) -> Self
+    {
+        self.ttl = ttl;
+        self
+    }
+
+    //Sets the maximum number of cached lists, at least 1
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self
+    {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    //Sets how long before expiry `refresh()` fetches a list again
+    pub fn with_refresh_ahead(mut self, refresh_ahead: Duration) -> Self
+    {
+        self.refresh_ahead = refresh_ahead;
+        self
+    }
+
+    //Sets the bit order the lists are read with, `BitOrder::Lsb0` for lists published before the spec-conformant ordering
+    pub fn with_bit_order(mut self, order: BitOrder) -> Self
+    {
+        self.order = order;
+        self
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.len() == 0
+    }
+
+    //Drops the cached version of `list_id`, if any
+    pub fn invalidate(&self, list_id: &str)
+    {
+        self.lock().remove(list_id);
+    }
+
+    pub fn clear(&self)
+    {
+        self.lock().clear();
+    }
+
+
+    //Returns the verified list `list_id` of `issuer` published under `index`, from the cache if still fresh
+    //or if nothing was published since it was fetched. A cached list of another issuer or index is not reused.
+    pub async fn list(&self, index: &str, list_id: &Url, issuer: &str) -> Result<Arc<RevocationList2020Credential>>
+    {
+        let now = Instant::now();
+        let cached = match self.lock().get_mut(list_id.as_str())
+        {
+            Some(entry) if entry.index == index && entry.issuer == issuer =>
+            {
+                entry.last_used = now;
+                if entry.expires_at > now
+                {
+                    return Ok(entry.list.clone());
+                }
+                Some((entry.list.clone(), entry.seen.clone()))
+            }
+            _ => None,
+        };
+
+        if let Some((list, seen)) = cached
+        {
+            if self.unchanged(index, &list, &seen).await
+            {
+                self.store(list_id.as_str(), index, issuer, list.clone(), seen);
+                return Ok(list);
+            }
+        }
+
+        let (list, seen) = fetch_verified_list(index, list_id, issuer, self.order, &self.resolver, &self.source).await?;
+        let list = Arc::new(list);
+        self.store(list_id.as_str(), index, issuer, list.clone(), seen);
+        Ok(list)
+    }
+
+    //true se la lista in cache non è scaduta e sotto `index` ci sono ancora e solo le pubblicazioni `seen`
+    async fn unchanged(&self, index: &str, list: &RevocationList2020Credential, seen: &[String]) -> bool
+    {
+        if max_age(list.credential(), self.ttl).is_zero()
+        {
+            return false;
+        }
+
+        match self.source.publication_ids(index).await
+        {
+            Ok(mut ids) =>
+            {
+                ids.sort();
+                ids == seen
+            }
+            Err(_) => false,
+        }
+    }
+
+
+    //Same as `Verifier::check_status`, reading the list through the cache
+    pub async fn check_status(&self, credential: &Credential) -> StatusVerdict
+    {
+        match self.check(credential).await
+        {
+            Ok((true, list)) => StatusVerdict::Revoked { list },
+            Ok((false, list)) => StatusVerdict::Valid { list },
+            Err(reason) => StatusVerdict::Unknown { reason },
+        }
+    }
+
+    async fn check(&self, credential: &Credential) -> Result<(bool, ListVersion)>
+    {
+        let reference = status_reference(credential)?;
+        let index = list_index(credential, reference.list_credential());
+        let list = self.list(&index, reference.list_credential(), credential.issuer.url().as_str()).await?;
+        read_status(&reference, &list)
+    }
+
+
+    //Refreshes the lists expiring within `refresh_ahead` and drops the expired ones that could not be refreshed.
+    //A list is downloaded and verified again only if new publications appeared under its index, otherwise
+    //the cached version is kept for another TTL. A fetched version older than the cached one is ignored.
+    //Returns the number of refreshed lists.
+    pub async fn refresh(&self) -> usize
+    {
+        let now = Instant::now();
+        let due: Vec<(String, Entry)> = self
+            .lock()
+            .iter()
+            .filter(|(_, entry)| entry.expires_at <= now + self.refresh_ahead)
+            .map(|(id, entry)| (id.clone(), entry.clone()))
+            .collect();
+
+        let mut refreshed = 0;
+        for (id, Entry { list: cached, index, issuer, seen, .. }) in due
+        {
+            if self.unchanged(&index, &cached, &seen).await
+            {
+                self.store(&id, &index, &issuer, cached, seen);
+                refreshed += 1;
+                continue;
+            }
+
+            let list_id = match Url::parse(&id)
+            {
+                Ok(list_id) => list_id,
+                Err(_) => continue,
+            };
+
+            if let Ok((list, seen)) = fetch_verified_list(&index, &list_id, &issuer, self.order, &self.resolver, &self.source).await
+            {
+                let current = self.lock().get(&id).map(|entry| entry.list.issuance_date());
+                if current.is_none_or(|date| list.issuance_date() >= date)
+                {
+                    self.store(&id, &index, &issuer, Arc::new(list), seen);
+                    refreshed += 1;
+                }
+            }
+        }
+
+        let now = Instant::now();
+        self.lock().retain(|_, entry| entry.expires_at > now);
+        refreshed
+    }
+
+    //Runs `refresh()` every `period` on the tokio runtime until the returned task is aborted
+    pub fn spawn_refresh(self: &Arc<Self>, period: Duration) -> JoinHandle<()>
+    where
+        R: 'static,
+        S: 'static,
+    {
+        let cache = Arc::clone(self);
+        tokio::spawn(async move
+        {
+            let mut interval = tokio::time::interval(period);
+            loop
+            {
+                interval.tick().await;
+                cache.refresh().await;
+            }
+        })
+    }
+
+
+    fn store(&self, list_id: &str, index: &str, issuer: &str, list: Arc<RevocationList2020Credential>, mut seen: Vec<String>)
+    {
+        seen.sort();
+        let now = Instant::now();
+        let expires_at = now + max_age(list.credential(), self.ttl);
+
+        let mut entries = self.lock();
+        if !entries.contains_key(list_id) && entries.len() >= self.max_entries
+        {
+            let oldest = entries.iter().min_by_key(|(_, entry)| entry.last_used).map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest
+            {
+                entries.remove(&oldest);
+            }
+        }
+
+        entries.insert(list_id.to_owned(), Entry
+        {
+            list,
+            index: index.to_owned(),
+            issuer: issuer.to_owned(),
+            seen,
+            expires_at,
+            last_used: now,
+        });
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>>
+    {
+        //Nessuna sezione critica può lasciare la mappa incoerente, quindi un lock avvelenato resta utilizzabile
+        self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+
+
+//Returns how long `credential` may be cached: the TTL, shortened to the remaining validity of the credential
+//according to `expirationDate` or `validUntil`, whichever comes first
+pub fn max_age(credential: &Credential, ttl: Duration) -> Duration
+{
+    let valid_until = match credential.properties.get("validUntil")
+    {
+        Some(Value::String(date)) => Timestamp::parse(date).ok(),
+        _ => None,
+    };
+
+    let expiry = [credential.expiration_date, valid_until].into_iter().flatten().min_by_key(Timestamp::to_unix);
+    match expiry
+    {
+        Some(expiry) =>
+        {
+            let remaining = expiry.to_unix().saturating_sub(Timestamp::now_utc().to_unix());
+            ttl.min(Duration::from_secs(u64::try_from(remaining).unwrap_or(0)))
+        }
+        None => ttl,
+    }
+}
@@ -0,0 +1,286 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use identity_core::common::Url;
+use identity_core::crypto::ProofOptions;
+use identity_credential::credential::Credential;
+use identity_did::did::DIDUrl;
+use identity_did::did::DID;
+use identity_iota::account::Account;
+
+use crate::CredentialError;
+use crate::Envelope::encode_credential;
+use crate::Envelope::Compression;
+use crate::IndexAllocator::AllocationStrategy;
+use crate::IssuerStore::IssuerStore;
+use crate::IssuerStore::ListRecord;
+use crate::Ledger::StatusListPublisher;
+use crate::Result;
+use crate::RevocationList2020;
+use crate::RevocationList2020Status::RevocationList2020Status;
+
+
+
+//Signs the list credentials before they are published
+#[async_trait]
+pub trait ListSigner
+{
+    async fn sign(&self, credential: &mut Credential) -> Result<()>;
+}
+
+//Signs with a verification method of an IOTA identity account, e.g. "#key-1"
+pub struct AccountSigner<'a>
+{
+    pub account: &'a Account,
+    pub fragment: &'a str,
+}
+
+#[async_trait]
+impl ListSigner for AccountSigner<'_>
+{
+    async fn sign(&self, credential: &mut Credential) -> Result<()>
+    {
+        self.account
+            .sign(self.fragment, credential, ProofOptions::default())
+            .await
+            .map_err(|e| CredentialError::Publish(format!("signing the list credential failed: {}", e)))
+    }
+}
+
+
+
+//Owns the revocation lists of an issuer. Indices are allocated from the newest list and a new list
+//`<base>/<n>` is opened when it fills up; every allocation is recorded so credentials can be revoked by id.
+#[derive(Debug, Clone)]
+pub struct ListManager
+{
+    base_id: String,
+    size: usize,
+    strategy: AllocationStrategy,
+
+    lists: Vec<ListRecord>,
+
+    //id della credenziale -> (list id, index), l'inverso dei `credentials` dei record
+    slots: BTreeMap<String, (String, u64)>,
+}
+
+
+impl ListManager
+{
+    //Creates a manager whose lists are `<base_id>/0`, `<base_id>/1`, ... of `size` kb each
+    pub fn new(base_id: &str, size: usize) -> Result<Self>
+    {
+        let mut manager = Self::empty(base_id, size);
+        manager.open_list()?;
+        Ok(manager)
+    }
+
+    //Reloads the lists `<base_id>/0`, `<base_id>/1`, ... saved in `store`, with the credentials allocated in them.
+    //New lists are created of `size` kb; without saved lists this is the same as `new`.
+    pub fn open<T>(base_id: &str, size: usize, store: &T) -> Result<Self>
+    where
+        T: IssuerStore + ?Sized,
+    {
+        let mut manager = Self::empty(base_id, size);
+        while let Some(record) = store.load(&format!("{}/{}", manager.base_id, manager.lists.len()))?
+        {
+            for (index, credential_id) in &record.credentials
+            {
+                manager.slots.insert(credential_id.clone(), (record.list_id().to_owned(), *index));
+            }
+            //Le nuove liste continuano con la strategia di quelle salvate
+            manager.strategy = record.allocator.strategy();
+            manager.lists.push(record);
+        }
+
+        if manager.lists.is_empty()
+        {
+            manager.open_list()?;
+        }
+        Ok(manager)
+    }
+
+    //Saves every list to `store`. Revocations are lost on restart until saved, so save before reporting them as done.
+    pub fn save<T>(&self, store: &T) -> Result<()>
+    where
+        T: IssuerStore + ?Sized,
+    {
+        store.save_all(&self.lists)
+    }
+
+    //Sets how the indices of every list are allocated
+    pub fn with_strategy(mut self, strategy: AllocationStrategy) -> Self
+    {
+        self.strategy = strategy;
+        for record in &mut self.lists
+        {
+            record.allocator = record.allocator.clone().with_strategy(strategy);
+        }
+        self
+    }
+
+    fn empty(base_id: &str, size: usize) -> Self
+    {
+        ListManager
+        {
+            base_id: base_id.trim_end_matches('/').to_owned(),
+            size,
+            strategy: AllocationStrategy::default(),
+            lists: Vec::new(),
+            slots: BTreeMap::new(),
+        }
+    }
+
+    fn open_list(&mut self) -> Result<&mut ListRecord>
+    {
+        let id = format!("{}/{}", self.base_id, self.lists.len());
+        Url::parse(&id).map_err(|e| CredentialError::InvalidListCredential(format!("list id '{}' is not a URL: {}", id, e)))?;
+
+        let mut record = ListRecord::new(RevocationList2020::new(&id, self.size)?);
+        record.allocator = record.allocator.with_strategy(self.strategy);
+        self.lists.push(record);
+        Ok(self.lists.last_mut().expect("list just pushed"))
+    }
+
+    fn record(&self, list_id: &str) -> Option<&ListRecord>
+    {
+        self.lists.iter().find(|record| record.list_id() == list_id)
+    }
+
+    fn record_mut(&mut self, list_id: &str) -> Option<&mut ListRecord>
+    {
+        self.lists.iter_mut().find(|record| record.list_id() == list_id)
+    }
+
+
+    //Assigns a free `(list id, index)` to `credential_id`, opening a new list if the current one is full.
+    //The record of the list is saved to `store` before returning, so after a crash the index is never
+    //handed out again; if saving fails nothing is allocated.
+    pub fn allocate<T>(&mut self, credential_id: &str, store: &T) -> Result<(String, u64)>
+    where
+        T: IssuerStore + ?Sized,
+    {
+        if self.slots.contains_key(credential_id)
+        {
+            return Err(CredentialError::CredentialAlreadyAllocated(credential_id.to_owned()));
+        }
+
+        let needs_list = self.lists.last().is_none_or(|record| record.allocator.is_full());
+        let record = if needs_list
+        {
+            self.open_list()?
+        }
+        else
+        {
+            self.lists.last_mut().expect("at least one list")
+        };
+
+        let index = record.allocator.allocate()?;
+        record.credentials.insert(index, credential_id.to_owned());
+        if let Err(e) = store.save(record)
+        {
+            record.credentials.remove(&index);
+            record.allocator.release(index);
+            if needs_list
+            {
+                self.lists.pop();
+            }
+            return Err(e);
+        }
+
+        let slot = (record.list_id().to_owned(), index);
+        self.slots.insert(credential_id.to_owned(), slot.clone());
+        Ok(slot)
+    }
+
+    //Allocates a slot for `credential_id`, saved to `store`, and returns the `credentialStatus` pointing to it
+    pub fn issue_status<D, T>(&mut self, credential_id: &str, id: DIDUrl<D>, store: &T) -> Result<RevocationList2020Status>
+    where
+        D: DID,
+        T: IssuerStore + ?Sized,
+    {
+        let (list_id, index) = self.allocate(credential_id, store)?;
+        let url = Url::parse(&list_id).map_err(|e| CredentialError::InvalidListCredential(e.to_string()))?;
+        Ok(RevocationList2020Status::new(id, index, url))
+    }
+
+
+    //Returns the credential the slot was allocated to
+    pub fn credential(&self, list_id: &str, index: u64) -> Option<&str>
+    {
+        self.record(list_id)?.credentials.get(&index).map(String::as_str)
+    }
+
+    //Returns the `(list id, index)` allocated to `credential_id`
+    pub fn slot(&self, credential_id: &str) -> Option<(&str, u64)>
+    {
+        self.slots.get(credential_id).map(|(list_id, index)| (list_id.as_str(), *index))
+    }
+
+    pub fn list(&self, list_id: &str) -> Option<&RevocationList2020>
+    {
+        self.record(list_id).map(|record| &record.list)
+    }
+
+    pub fn lists(&self) -> impl Iterator<Item = &RevocationList2020>
+    {
+        self.lists.iter().map(|record| &record.list)
+    }
+
+    //Returns the ids of the lists changed, or never published, since the last publication
+    pub fn dirty_lists(&self) -> Vec<&str>
+    {
+        self.lists.iter().filter(|record| record.needs_publication()).map(ListRecord::list_id).collect()
+    }
+
+
+    fn slot_of(&self, credential_id: &str) -> Result<(String, u64)>
+    {
+        self.slots.get(credential_id).cloned().ok_or_else(|| CredentialError::UnknownCredential(credential_id.to_owned()))
+    }
+
+    //Revokes the credential, returning false if it was already revoked
+    pub fn revoke(&mut self, credential_id: &str) -> Result<bool>
+    {
+        let (list_id, index) = self.slot_of(credential_id)?;
+        let record = self.record_mut(&list_id).expect("slots only point to managed lists");
+        let was_revoked = record.list.is_revoked(index)?;
+        record.list.revoke(index)?;
+        Ok(!was_revoked)
+    }
+
+    //Reinstates the credential, returning false if it was not revoked
+    pub fn reset(&mut self, credential_id: &str) -> Result<bool>
+    {
+        let (list_id, index) = self.slot_of(credential_id)?;
+        let record = self.record_mut(&list_id).expect("slots only point to managed lists");
+        let was_revoked = record.list.is_revoked(index)?;
+        record.list.reset(index)?;
+        Ok(was_revoked)
+    }
+
+
+    //Builds, signs and publishes the credential of every dirty list, each under its list id as ledger index.
+    //Returns the published list ids with their publication ids. A list stays dirty if its publication fails.
+    pub async fn publish_dirty<S, P>(&mut self, issuer: &Url, signer: &S, publisher: &P) -> Result<Vec<(String, String)>>
+    where
+        S: ListSigner + ?Sized,
+        P: StatusListPublisher + ?Sized,
+    {
+        let mut published = Vec::new();
+        for record in self.lists.iter_mut().filter(|record| record.needs_publication())
+        {
+            let mut credential = record.list_credential(issuer)?;
+            signer.sign(&mut credential).await?;
+
+            let payload = encode_credential(&credential, Compression::Gzip)?;
+            let publication = publisher.publish(record.list_id(), &payload).await?;
+            record.set_published(&credential, &publication);
+            published.push((record.list_id().to_owned(), publication));
+        }
+        Ok(published)
+    }
+}
@@ -0,0 +1,189 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use identity_core::common::Context;
+use identity_core::common::Object;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::common::Value;
+use identity_credential::credential::Credential;
+use identity_credential::credential::CredentialBuilder;
+use identity_credential::credential::Subject;
+
+use crate::BitOrder;
+use crate::CredentialError;
+use crate::Result;
+use crate::RevocationList2020;
+
+
+
+//Costanti della VC lista
+pub const REVOCATION_LIST_2020_CONTEXT: &str = "https://w3id.org/vc-revocation-list-2020/v1";
+pub const REVOCATION_LIST_2020_CREDENTIAL_TYPE: &str = "RevocationList2020Credential";
+
+
+
+//A [`RevocationList2020`] wrapped in the Verifiable Credential that publishes it.
+//Built by the issuer from a list, or parsed and validated by a verifier from a fetched credential.
+#[derive(Debug, Clone)]
+pub struct RevocationList2020Credential
+{
+    credential: Credential,
+    list: RevocationList2020,
+}
+
+
+impl RevocationList2020Credential
+{
+    //The type name of the list credential
+    pub const TYPE: &'static str = REVOCATION_LIST_2020_CREDENTIAL_TYPE;
+
+
+    //Builds the (unsigned) list credential issued by `issuer`, using the list id as credential id
+    pub fn new(issuer: Url, mut list: RevocationList2020) -> Result<Self>
+    {
+        let id = Url::parse(list.id()).map_err(|e|
+        {
+            CredentialError::InvalidListCredential(format!("list id '{}' is not a URL: {}", list.id(), e))
+        })?;
+
+        let mut properties = Object::new();
+        properties.insert("type".to_owned(), Value::String(RevocationList2020::TYPE.to_owned()));
+        properties.insert("encodedList".to_owned(), Value::String(list.encode()?.to_owned()));
+        let subject_id = id.join("#list").map_err(|e| CredentialError::InvalidListCredential(e.to_string()))?;
+        let subject = Subject::with_id_and_properties(subject_id, properties);
+
+        let credential: Credential = CredentialBuilder::default()
+            .id(id)
+            .context(Url::parse(REVOCATION_LIST_2020_CONTEXT).expect("valid context url"))
+            .type_(Self::TYPE)
+            .issuer(issuer)
+            .subject(subject)
+            .build()
+            .map_err(|e| CredentialError::InvalidListCredential(e.to_string()))?;
+
+        Ok(RevocationList2020Credential { credential, list })
+    }
+
+
+    //Returns the wrapped credential
+    pub fn credential(&self) -> &Credential
+    {
+        &self.credential
+    }
+
+    //Returns the wrapped credential, e.g. to sign it
+    pub fn credential_mut(&mut self) -> &mut Credential
+    {
+        &mut self.credential
+    }
+
+    pub fn into_credential(self) -> Credential
+    {
+        self.credential
+    }
+
+    //Returns the decoded revocation list
+    pub fn list(&self) -> &RevocationList2020
+    {
+        &self.list
+    }
+
+    pub fn into_list(self) -> RevocationList2020
+    {
+        self.list
+    }
+
+    //Reads the list with `order`, e.g. `BitOrder::Lsb0` for lists published before the spec-conformant ordering
+    pub fn with_bit_order(mut self, order: BitOrder) -> Self
+    {
+        self.list = self.list.with_bit_order(order);
+        self
+    }
+
+    //Returns the id of the list credential, which is the list id
+    pub fn id(&self) -> &str
+    {
+        self.list.id()
+    }
+
+    pub fn issuer(&self) -> &Url
+    {
+        self.credential.issuer.url()
+    }
+
+    pub fn issuance_date(&self) -> Timestamp
+    {
+        self.credential.issuance_date
+    }
+
+
+    //Validazione della struttura della VC lista
+    fn check(credential: &Credential) -> Result<()>
+    {
+        let invalid = |msg: String| Err(CredentialError::InvalidListCredential(msg));
+
+        if !credential.context.iter().any(|c| matches!(c, Context::Url(url) if url.as_str() == REVOCATION_LIST_2020_CONTEXT))
+        {
+            return invalid(format!("missing context '{}'", REVOCATION_LIST_2020_CONTEXT));
+        }
+
+        if !credential.types.iter().any(|t| t == Self::TYPE)
+        {
+            return invalid(format!("missing type '{}'", Self::TYPE));
+        }
+
+        if credential.id.is_none()
+        {
+            return invalid("missing credential id".to_owned());
+        }
+
+        if credential.credential_subject.len() != 1
+        {
+            return invalid(format!("expected one credential subject, got {}", credential.credential_subject.len()));
+        }
+
+        let subject = &credential.credential_subject.as_slice()[0];
+        if subject.id.is_none()
+        {
+            return invalid("missing credential subject id".to_owned());
+        }
+
+        match subject.properties.get("type")
+        {
+            Some(Value::String(t)) if t == RevocationList2020::TYPE => Ok(()),
+            other => invalid(format!("expected subject type '{}', got {:?}", RevocationList2020::TYPE, other)),
+        }
+    }
+}
+
+
+
+impl TryFrom<Credential> for RevocationList2020Credential
+{
+    type Error = CredentialError;
+
+    fn try_from(credential: Credential) -> Result<Self>
+    {
+        Self::check(&credential)?;
+
+        let id = credential.id.as_ref().map(|id| id.to_string()).unwrap_or_default();
+        let encoded_list = match credential.credential_subject.as_slice()[0].properties.get("encodedList")
+        {
+            Some(Value::String(el)) => el.clone(),
+            _ => return Err(CredentialError::InvalidListCredential("missing encodedList".to_owned())),
+        };
+
+        let list = RevocationList2020::new_withList(&id, &encoded_list)?;
+
+        Ok(RevocationList2020Credential { credential, list })
+    }
+}
+
+impl From<RevocationList2020Credential> for Credential
+{
+    fn from(list_credential: RevocationList2020Credential) -> Self
+    {
+        list_credential.credential
+    }
+}
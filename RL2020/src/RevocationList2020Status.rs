@@ -1,8 +1,6 @@
 #![allow(dead_code, unused)]
 #![allow(non_snake_case)]
 
-use std::str::FromStr;
-
 use identity_core::common::Object;
 use identity_core::common::Url;
 use identity_core::common::Value;
@@ -10,71 +8,65 @@ use identity_did::did::DIDUrl;
 use identity_did::did::DID;
 
 use identity_credential::credential::Status;
-use identity_credential::error::Error;
-use identity_credential::error::Result;
+
+use crate::CredentialStatus::status_index;
+use crate::CredentialStatus::status_list_credential;
+use crate::CredentialError;
+use crate::Result;
 
 
 
 //Information used to determine the current status of a [`Credential`][identity_credential::credential::Credential].
+//The location of the list credential is validated once, when the entry is built.
 #[derive(Clone, Debug, PartialEq)]
-pub struct RevocationList2020Status(Status);
+pub struct RevocationList2020Status(Status, Url);
 
 
 
 impl RevocationList2020Status 
 {
   const INDEX_PROPERTY_NAME: &'static str = "revocationListIndex";
+  const CREDENTIAL_PROPERTY_NAME: &'static str = "revocationListCredential";
   
   
   //The type name of the revocation list.
   pub const TYPE: &'static str = "RevocationList2020";
 
   //Creates a new `RevocationList2020Status`
-  pub fn new<D: DID>(id: DIDUrl<D>, index: u32, urlLista: &str) -> Self 
+  pub fn new<D: DID>(id: DIDUrl<D>, index: u64, urlLista: Url) -> Self 
   {
     let mut object = Object::new();
     object.insert(Self::INDEX_PROPERTY_NAME.to_owned(), Value::String(index.to_string()));
-	  object.insert("revocationListCredential".to_string(), Value::String(urlLista.to_string()));
+	  object.insert(Self::CREDENTIAL_PROPERTY_NAME.to_owned(), Value::String(urlLista.to_string()));
 	
 	
-    RevocationList2020Status(Status::new_with_properties(
-      Url::from(id),
-      Self::TYPE.to_owned(),
-      object,
-    ))
+    RevocationList2020Status(
+      Status::new_with_properties(Url::from(id), Self::TYPE.to_owned(), object),
+      urlLista,
+    )
   }
 
   //Returns the [`DIDUrl`] of the revlist status.
   pub fn id<D: DID>(&self) -> Result<DIDUrl<D>> 
   {
     DIDUrl::parse(self.0.id.as_str())
-      .map_err(|err| Error::InvalidStatus(format!("invalid DID Url '{}': {:?}", self.0.id, err)))
+      .map_err(|err| CredentialError::InvalidStatus(format!("invalid DID Url '{}': {:?}", self.0.id, err)))
   }
 
 
 
 
+  //Returns the location of the revocation list credential.
+  pub fn list_credential(&self) -> &Url
+  {
+    &self.1
+  }
+
+
   //Returns the index of the credential in the issuer's revocation list if it can be decoded.
-  pub fn index(&self) -> Result<u32> 
+  pub fn index(&self) -> Result<u64> 
   {
-    if let Some(Value::String(index)) = self.0.properties.get(Self::INDEX_PROPERTY_NAME) 
-	{
-      u32::from_str(index).map_err(|err| 
-	  {
-        Error::InvalidStatus(format!(
-          "expected {} to be an unsigned 32-bit integer: {}",
-          Self::INDEX_PROPERTY_NAME,
-          err
-        ))
-      })
-    } 
-	else 
-	{
-      Err(Error::InvalidStatus(format!(
-        "expected {} to be an unsigned 32-bit integer expressed as a string",
-        Self::INDEX_PROPERTY_NAME
-      )))
-    }
+    status_index(&self.0.properties, Self::INDEX_PROPERTY_NAME)
   }
 }
 
@@ -82,13 +74,13 @@ impl RevocationList2020Status
 
 impl TryFrom<Status> for RevocationList2020Status 
 {
-  type Error = Error;
+  type Error = CredentialError;
 
   fn try_from(status: Status) -> Result<Self> 
   {
     if status.type_ != Self::TYPE 
 	{
-      Err(Error::InvalidStatus(format!(
+      Err(CredentialError::InvalidStatus(format!(
         "expected type '{}', got '{}'",
         Self::TYPE,
         status.type_
@@ -96,21 +88,24 @@ impl TryFrom<Status> for RevocationList2020Status
     } 
 	else if !status.properties.contains_key(Self::INDEX_PROPERTY_NAME) 
 	{
-      Err(Error::InvalidStatus(format!(
+      Err(CredentialError::InvalidStatus(format!(
         "missing required property '{}'",
         Self::INDEX_PROPERTY_NAME
       )))
     }
-	else if !status.properties.contains_key("revocationListCredential") 
+	else if !status.properties.contains_key(Self::CREDENTIAL_PROPERTY_NAME) 
 	{
-      Err(Error::InvalidStatus(format!(
+      Err(CredentialError::InvalidStatus(format!(
         "missing required property '{}'",
-        "revocationListCredential"
+        Self::CREDENTIAL_PROPERTY_NAME
       )))
     } 	
 	else 
 	{
-      Ok(Self(status))
+      let list_credential = status_list_credential(&status.properties, Self::CREDENTIAL_PROPERTY_NAME)?;
+      let status = Self(status, list_credential);
+      status.index()?;
+      Ok(status)
     }
   }
 }
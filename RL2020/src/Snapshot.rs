@@ -0,0 +1,266 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::fmt::Display;
+use std::io::Read;
+use std::io::Write;
+
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use flate2::Crc;
+use serde::de;
+use serde::ser;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Bitstring::{Bitstring, Codec, EncodedList};
+use crate::BitOrder;
+use crate::CodingStage;
+use crate::CredentialError;
+use crate::Result;
+use crate::RevocationList2020;
+use crate::MAX_BITSTRING_SIZE_KB;
+use crate::MIN_BITSTRING_SIZE_KN;
+use crate::REVOCATION_LIST_2020_TYPE;
+
+
+
+//Formato binario di una RevocationList2020, per salvarla e ricaricarla senza passare dalla VC.
+//Gli interi sono little endian:
+//
+//  magic       4 byte   "RL20"
+//  version     u8       SNAPSHOT_VERSION
+//  flags       u8       FLAG_LSB0 | FLAG_DIRTY
+//  id          u16 lunghezza in byte + UTF-8
+//  bitstring   u32 lunghezza in byte + u32 lunghezza compressa + byte ZLIB
+//  checksum    u32      CRC-32 di tutti i byte precedenti
+//
+//La bitstring è salvata una sola volta, la encodedList viene ricalcolata al caricamento.
+//Come per `RevocationList2020::new`, la bitstring è di un numero intero di kb tra 16 e 128.
+//Serde usa lo stesso formato, codificato in base64, così ogni lista caricata passa dagli stessi controlli.
+pub const SNAPSHOT_MAGIC: &[u8; 4] = b"RL20";
+pub const SNAPSHOT_VERSION: u8 = 1;
+
+//La lista indirizza i bit di ogni byte dal meno significativo
+const FLAG_LSB0: u8 = 0x01;
+
+//La lista è stata modificata dopo l'ultimo `encode()`
+const FLAG_DIRTY: u8 = 0x02;
+
+const CHECKSUM_LEN: usize = 4;
+
+
+
+impl RevocationList2020
+{
+    //Serializes the list in the versioned binary snapshot format. The bitstring is stored once,
+    //compressed, together with the id, the bit order and whether the list changed since the last `encode()`.
+    pub fn to_snapshot(&self) -> Result<Vec<u8>>
+    {
+        //Solo liste che `from_snapshot` accetta, ovvero della dimensione di quelle create da `new`
+        let bits = self.list.bits().as_bytes();
+        check_size(bits.len()).map_err(|message| CredentialError::encoding(CodingStage::Snapshot, message))?;
+        let id_len = u16::try_from(self.id.len())
+            .map_err(|_| CredentialError::encoding(CodingStage::Snapshot, "id is longer than 65535 bytes"))?;
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bits).map_err(|e| CredentialError::encoding(CodingStage::Zlib, e))?;
+        let compressed = encoder.finish().map_err(|e| CredentialError::encoding(CodingStage::Zlib, e))?;
+
+        let mut flags = 0;
+        if self.bit_order() == BitOrder::Lsb0
+        {
+            flags |= FLAG_LSB0;
+        }
+        if self.is_dirty()
+        {
+            flags |= FLAG_DIRTY;
+        }
+
+        //Le lunghezze stanno in u32: la bitstring è limitata a MAX_BITSTRING_SIZE_KB
+        let mut out = Vec::with_capacity(20 + self.id.len() + compressed.len());
+        out.extend_from_slice(SNAPSHOT_MAGIC);
+        out.push(SNAPSHOT_VERSION);
+        out.push(flags);
+        out.extend_from_slice(&id_len.to_le_bytes());
+        out.extend_from_slice(self.id.as_bytes());
+        out.extend_from_slice(&(bits.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        out.extend_from_slice(&compressed);
+
+        let mut crc = Crc::new();
+        crc.update(&out);
+        out.extend_from_slice(&crc.sum().to_le_bytes());
+        Ok(out)
+    }
+
+
+    //Loads a list written by `to_snapshot`. The format, the version, the checksum and every length are
+    //validated, so truncated or corrupted snapshots are rejected with an error.
+    pub fn from_snapshot(data: &[u8]) -> Result<Self>
+    {
+        let mut reader = SnapshotReader { data };
+        if reader.take(SNAPSHOT_MAGIC.len())? != SNAPSHOT_MAGIC
+        {
+            return Err(invalid("not a revocation list snapshot"));
+        }
+        let version = reader.u8()?;
+        if version != SNAPSHOT_VERSION
+        {
+            return Err(invalid(format!("unsupported snapshot version {}", version)));
+        }
+
+        //Il checksum copre anche l'intestazione, quindi lo verifico prima di leggere gli altri campi
+        let header_len = SNAPSHOT_MAGIC.len() + 1;
+        if data.len() < header_len + CHECKSUM_LEN
+        {
+            return Err(invalid("snapshot is truncated"));
+        }
+        let (body, checksum) = data.split_at(data.len() - CHECKSUM_LEN);
+        let mut crc = Crc::new();
+        crc.update(body);
+        if crc.sum().to_le_bytes() != checksum
+        {
+            return Err(invalid("checksum mismatch"));
+        }
+        let mut reader = SnapshotReader { data: &body[header_len..] };
+
+        let flags = reader.u8()?;
+        if flags & !(FLAG_LSB0 | FLAG_DIRTY) != 0
+        {
+            return Err(invalid(format!("unknown flags {:#04x}", flags)));
+        }
+
+        let id_len = usize::from(reader.u16()?);
+        let id = std::str::from_utf8(reader.take(id_len)?).map_err(invalid)?;
+        if id.trim().is_empty()
+        {
+            return Err(CredentialError::EmptyId);
+        }
+
+        let bit_len = reader.u32()? as usize;
+        check_size(bit_len).map_err(invalid)?;
+        let compressed_len = reader.u32()? as usize;
+        let compressed = reader.take(compressed_len)?;
+        if !reader.data.is_empty()
+        {
+            return Err(invalid("trailing bytes after the bitstring"));
+        }
+
+        //Non decomprimo oltre la lunghezza dichiarata
+        let mut bits = Vec::with_capacity(bit_len);
+        ZlibDecoder::new(compressed)
+            .take(bit_len as u64 + 1)
+            .read_to_end(&mut bits)
+            .map_err(|e| CredentialError::decoding(CodingStage::Zlib, e))?;
+        if bits.len() != bit_len
+        {
+            return Err(invalid(format!("bitstring is {} bytes, expected {}", bits.len(), bit_len)));
+        }
+
+        let mut bit_set = Bitstring::from_bytes(bits);
+        bit_set.set_order(if flags & FLAG_LSB0 != 0 { BitOrder::Lsb0 } else { BitOrder::Msb0 });
+
+        //Una lista modificata ricalcola comunque la encodedList a ogni lettura
+        let list = if flags & FLAG_DIRTY != 0
+        {
+            EncodedList::modified(bit_set, Codec::ZlibBase64)
+        }
+        else
+        {
+            EncodedList::new(bit_set, Codec::ZlibBase64)?
+        };
+
+        Ok(RevocationList2020
+        {
+            id: id.to_owned(),
+            typ: String::from(REVOCATION_LIST_2020_TYPE),
+            list,
+        })
+    }
+}
+
+
+
+//Serializes the list as its base64 encoded snapshot
+impl Serialize for RevocationList2020
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    {
+        let snapshot = self.to_snapshot().map_err(ser::Error::custom)?;
+        serializer.serialize_str(&base64::encode_config(snapshot, base64::STANDARD))
+    }
+}
+
+//Loads a list serialized by `Serialize`, validated as `from_snapshot` does
+impl<'de> Deserialize<'de> for RevocationList2020
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error>
+    {
+        let encoded = String::deserialize(deserializer)?;
+        let snapshot = base64::decode_config(encoded, base64::STANDARD)
+            .map_err(|e| de::Error::custom(CredentialError::decoding(CodingStage::Base64, e)))?;
+        RevocationList2020::from_snapshot(&snapshot).map_err(de::Error::custom)
+    }
+}
+
+
+
+//La bitstring deve essere di un numero intero di kb tra MIN_BITSTRING_SIZE_KN e MAX_BITSTRING_SIZE_KB,
+//come per `RevocationList2020::new`
+fn check_size(bit_len: usize) -> std::result::Result<(), String>
+{
+    if !bit_len.is_multiple_of(1024) || !(MIN_BITSTRING_SIZE_KN..=MAX_BITSTRING_SIZE_KB).contains(&(bit_len / 1024))
+    {
+        return Err(format!(
+            "bitstring of {} bytes is not a whole number of kb between {} and {}",
+            bit_len, MIN_BITSTRING_SIZE_KN, MAX_BITSTRING_SIZE_KB
+        ));
+    }
+    Ok(())
+}
+
+fn invalid(message: impl Display) -> CredentialError
+{
+    CredentialError::decoding(CodingStage::Snapshot, message)
+}
+
+
+//Lettura sequenziale dei campi, con controllo dei limiti
+struct SnapshotReader<'a>
+{
+    data: &'a [u8],
+}
+
+impl<'a> SnapshotReader<'a>
+{
+    fn take(&mut self, len: usize) -> Result<&'a [u8]>
+    {
+        if self.data.len() < len
+        {
+            return Err(invalid("snapshot is truncated"));
+        }
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8>
+    {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16>
+    {
+        let mut buf = [0; 2];
+        buf.copy_from_slice(self.take(2)?);
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    fn u32(&mut self) -> Result<u32>
+    {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+}
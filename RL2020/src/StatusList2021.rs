@@ -0,0 +1,199 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::Bitstring::Bitstring;
+use crate::Bitstring::Codec;
+use crate::Bitstring::EncodedList;
+use crate::BitOrder;
+use crate::CredentialError;
+use crate::Result;
+use crate::MAX_BITSTRING_SIZE_KB;
+use crate::MIN_BITSTRING_SIZE_KN;
+
+
+
+//Costanti tipo
+pub const STATUS_LIST_2021_TYPE: &str = "StatusList2021";
+
+
+
+//Scopo della lista, ovvero il significato di un bit settato a 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusPurpose
+{
+    Revocation,
+    Suspension,
+}
+
+impl StatusPurpose
+{
+    pub fn as_str(&self) -> &'static str
+    {
+        match self
+        {
+            StatusPurpose::Revocation => "revocation",
+            StatusPurpose::Suspension => "suspension",
+        }
+    }
+}
+
+impl Display for StatusPurpose
+{
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result
+    {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for StatusPurpose
+{
+    type Err = CredentialError;
+
+    fn from_str(s: &str) -> Result<Self>
+    {
+        match s
+        {
+            "revocation" => Ok(StatusPurpose::Revocation),
+            "suspension" => Ok(StatusPurpose::Suspension),
+            other => Err(CredentialError::InvalidStatus(format!("unknown status purpose '{}'", other))),
+        }
+    }
+}
+
+
+
+//A StatusList2021 bitstring: like `RevocationList2020` but GZIP compressed and with a `statusPurpose`.
+#[derive(Debug, Clone)]
+pub struct StatusList2021
+{
+    id: String,
+
+    purpose: StatusPurpose,
+
+    list: EncodedList,
+}
+
+
+impl StatusList2021
+{
+    //The type name of the list
+    pub const TYPE: &'static str = STATUS_LIST_2021_TYPE;
+
+
+    // Constructs a new empty [`StatusList2021`] of `size` kb.
+    pub fn new(id: &str, purpose: StatusPurpose, size: usize) -> Result<Self>
+    {
+        if !(MIN_BITSTRING_SIZE_KN..=MAX_BITSTRING_SIZE_KB).contains(&size)
+        {
+            return Err(CredentialError::InvalidSize
+            {
+                size,
+                min: MIN_BITSTRING_SIZE_KN,
+                max: MAX_BITSTRING_SIZE_KB,
+            });
+        }
+
+        if id.trim().is_empty()
+        {
+            return Err(CredentialError::EmptyId);
+        }
+
+        Ok(StatusList2021
+        {
+            id: String::from(id),
+            purpose,
+            list: EncodedList::new(Bitstring::new(size * 1024), Codec::GzipBase64Url)?,
+        })
+    }
+
+
+    //Crea lista partendo da una encodedList pubblicata
+    pub fn new_withList(id: &str, purpose: StatusPurpose, encList: &str) -> Result<Self>
+    {
+        if id.trim().is_empty()
+        {
+            return Err(CredentialError::EmptyId);
+        }
+
+        Ok(StatusList2021
+        {
+            id: String::from(id),
+            purpose,
+            list: EncodedList::unpack(encList, Codec::GzipBase64Url)?,
+        })
+    }
+
+
+    pub fn id(&self) -> &str
+    {
+        &self.id
+    }
+
+    pub fn purpose(&self) -> StatusPurpose
+    {
+        self.purpose
+    }
+
+    //Returns numero di entry/bit disponibili
+    pub fn capacity(&self) -> usize
+    {
+        self.list.bits().capacity()
+    }
+
+    //Size returns the size of the bitset in kb
+    pub fn size(&self) -> usize
+    {
+        self.list.bits().byte_len() / 1024
+    }
+
+    pub fn bit_order(&self) -> BitOrder
+    {
+        self.list.bits().order()
+    }
+
+
+    //Returns the encoded list, recomputed without caching if the bitstring changed since the last `encode()`
+    pub fn get_encList(&self) -> Result<String>
+    {
+        self.list.get_encList()
+    }
+
+    //Compresses and encodes the bitstring if needed and caches the result
+    pub fn encode(&mut self) -> Result<&str>
+    {
+        self.list.encode()
+    }
+
+    pub fn is_dirty(&self) -> bool
+    {
+        self.list.is_dirty()
+    }
+
+
+    //Returns `true` if the status at `index` is set, i.e. revoked or suspended depending on the purpose
+    pub fn get(&self, index: u64) -> Result<bool>
+    {
+        self.list.bits().get(index)
+    }
+
+    //Sets the status at `index`
+    pub fn set(&mut self, index: u64, value: bool) -> Result<()>
+    {
+        self.list.set(index, value)?;
+        Ok(())
+    }
+
+    //Applies all `changes` atomically, returning the indices whose state changed
+    pub fn apply<I>(&mut self, changes: I) -> Result<Vec<u64>>
+    where
+    I: IntoIterator<Item = (u64, bool)>,
+    {
+        self.list.apply(changes.into_iter().collect())
+    }
+}
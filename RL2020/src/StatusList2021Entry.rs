@@ -0,0 +1,127 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use std::str::FromStr;
+
+use identity_core::common::Object;
+use identity_core::common::Url;
+use identity_core::common::Value;
+use identity_did::did::DIDUrl;
+use identity_did::did::DID;
+
+use identity_credential::credential::Status;
+
+use crate::CredentialStatus::status_index;
+use crate::CredentialStatus::status_list_credential;
+use crate::CredentialError;
+use crate::Result;
+use crate::StatusList2021::StatusPurpose;
+
+
+
+//A `credentialStatus` entry pointing into a StatusList2021 credential.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusList2021Entry(Status, Url);
+
+
+
+impl StatusList2021Entry
+{
+  const PURPOSE_PROPERTY_NAME: &'static str = "statusPurpose";
+  const INDEX_PROPERTY_NAME: &'static str = "statusListIndex";
+  const CREDENTIAL_PROPERTY_NAME: &'static str = "statusListCredential";
+
+
+  //The type name of the status entry.
+  pub const TYPE: &'static str = "StatusList2021Entry";
+
+  //Creates a new `StatusList2021Entry`
+  pub fn new<D: DID>(id: DIDUrl<D>, purpose: StatusPurpose, index: u64, urlLista: Url) -> Self
+  {
+    let mut object = Object::new();
+    object.insert(Self::PURPOSE_PROPERTY_NAME.to_owned(), Value::String(purpose.to_string()));
+    object.insert(Self::INDEX_PROPERTY_NAME.to_owned(), Value::String(index.to_string()));
+    object.insert(Self::CREDENTIAL_PROPERTY_NAME.to_owned(), Value::String(urlLista.to_string()));
+
+    StatusList2021Entry(
+      Status::new_with_properties(Url::from(id), Self::TYPE.to_owned(), object),
+      urlLista,
+    )
+  }
+
+  //Returns the [`DIDUrl`] of the status entry.
+  pub fn id<D: DID>(&self) -> Result<DIDUrl<D>>
+  {
+    DIDUrl::parse(self.0.id.as_str())
+      .map_err(|err| CredentialError::InvalidStatus(format!("invalid DID Url '{}': {:?}", self.0.id, err)))
+  }
+
+  //Returns the purpose of the referenced status list.
+  pub fn purpose(&self) -> Result<StatusPurpose>
+  {
+    match self.0.properties.get(Self::PURPOSE_PROPERTY_NAME)
+    {
+      Some(Value::String(purpose)) => StatusPurpose::from_str(purpose),
+      _ => Err(CredentialError::InvalidStatus(format!(
+        "expected {} to be a string",
+        Self::PURPOSE_PROPERTY_NAME
+      ))),
+    }
+  }
+
+  //Returns the location of the status list credential.
+  pub fn list_credential(&self) -> &Url
+  {
+    &self.1
+  }
+
+  //Returns the index of the credential in the issuer's status list if it can be decoded.
+  pub fn index(&self) -> Result<u64>
+  {
+    status_index(&self.0.properties, Self::INDEX_PROPERTY_NAME)
+  }
+}
+
+
+
+impl TryFrom<Status> for StatusList2021Entry
+{
+  type Error = CredentialError;
+
+  fn try_from(status: Status) -> Result<Self>
+  {
+    if status.type_ != Self::TYPE
+    {
+      return Err(CredentialError::InvalidStatus(format!(
+        "expected type '{}', got '{}'",
+        Self::TYPE,
+        status.type_
+      )));
+    }
+
+    for property in [Self::PURPOSE_PROPERTY_NAME, Self::INDEX_PROPERTY_NAME, Self::CREDENTIAL_PROPERTY_NAME]
+    {
+      if !status.properties.contains_key(property)
+      {
+        return Err(CredentialError::InvalidStatus(format!(
+          "missing required property '{}'",
+          property
+        )));
+      }
+    }
+
+    let list_credential = status_list_credential(&status.properties, Self::CREDENTIAL_PROPERTY_NAME)?;
+    let entry = Self(status, list_credential);
+    entry.purpose()?;
+    entry.index()?;
+    Ok(entry)
+  }
+}
+
+impl From<StatusList2021Entry> for Status
+{
+  fn from(entry: StatusList2021Entry) -> Self
+  {
+    entry.0
+  }
+}
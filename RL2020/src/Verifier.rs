@@ -0,0 +1,313 @@
+#![allow(dead_code, unused)]
+#![allow(non_snake_case)]
+
+use async_trait::async_trait;
+use identity_core::common::Timestamp;
+use identity_core::common::Url;
+use identity_core::common::Value;
+use identity_credential::credential::Credential;
+use identity_did::did::DID;
+use identity_did::verifiable::VerifierOptions;
+use identity_iota::client::Resolver;
+use identity_iota::iota_core::IotaDID;
+use identity_iota::iota_core::IotaDocument;
+
+use crate::BitOrder;
+use crate::CredentialStatus::status_reference;
+use crate::CredentialStatus::StatusReference;
+use crate::CredentialError;
+use crate::Envelope::decode_credential;
+use crate::Ledger::Publication;
+use crate::Ledger::StatusListSource;
+use crate::Result;
+use crate::RevocationList2020Credential::RevocationList2020Credential;
+
+
+
+//Proprietà della credenziale con l'indice dei messaggi della sua lista sul ledger
+pub const LIST_INDEX_PROPERTY: &str = "IndiceRL";
+
+
+
+//Resolves the DID document of a credential issuer
+#[async_trait]
+pub trait IssuerResolver
+{
+    async fn resolve_issuer(&self, did: &str) -> Result<IotaDocument>;
+}
+
+#[async_trait]
+impl IssuerResolver for Resolver
+{
+    async fn resolve_issuer(&self, did: &str) -> Result<IotaDocument>
+    {
+        let did = IotaDID::parse(did).map_err(|e| CredentialError::IssuerResolution(e.to_string()))?;
+        let resolved = self.resolve(&did).await.map_err(|e| CredentialError::IssuerResolution(e.to_string()))?;
+        Ok(resolved.document)
+    }
+}
+
+//A known DID document resolves only its own DID, e.g. for verifiers that trust a single issuer
+#[async_trait]
+impl IssuerResolver for IotaDocument
+{
+    async fn resolve_issuer(&self, did: &str) -> Result<IotaDocument>
+    {
+        if self.id().as_str() != did
+        {
+            return Err(CredentialError::IssuerResolution(format!("unknown issuer '{}'", did)));
+        }
+        Ok(self.clone())
+    }
+}
+
+
+
+//Versione candidata della lista: issuance date e payload servono per l'ordinamento
+struct Candidate
+{
+    list: RevocationList2020Credential,
+    payload: Vec<u8>,
+}
+
+
+//Esito della verifica di una VC lista
+#[derive(Debug, Clone)]
+pub enum ListVerdict
+{
+    //The list credential passed every check
+    Valid(Box<RevocationList2020Credential>),
+
+    //The list credential is not the one referenced by `revocationListCredential`
+    WrongList { expected: String, found: Option<String> },
+
+    //The list credential, or the DID document used to verify it, belongs to another issuer
+    WrongIssuer { expected: String, found: String },
+
+    //The proof is missing or was not produced by the issuer's DID document
+    InvalidSignature(String),
+
+    //Context, types or subject are not those of a RevocationList2020Credential
+    Malformed(String),
+}
+
+impl ListVerdict
+{
+    pub fn is_valid(&self) -> bool
+    {
+        matches!(self, ListVerdict::Valid(_))
+    }
+
+    //Returns the verified list, or the reason of the failure as an error
+    pub fn into_list(self) -> Result<RevocationList2020Credential>
+    {
+        match self
+        {
+            ListVerdict::Valid(list) => Ok(*list),
+            ListVerdict::WrongList { expected, found } => Err(CredentialError::InvalidListCredential(format!(
+                "expected list credential '{}', got {:?}",
+                expected, found
+            ))),
+            ListVerdict::WrongIssuer { expected, found } => Err(CredentialError::InvalidListCredential(format!(
+                "expected issuer '{}', got '{}'",
+                expected, found
+            ))),
+            ListVerdict::InvalidSignature(message) => Err(CredentialError::InvalidListCredential(format!(
+                "invalid signature: {}",
+                message
+            ))),
+            ListVerdict::Malformed(message) => Err(CredentialError::InvalidListCredential(message)),
+        }
+    }
+}
+
+
+//Checks that `list` is the list credential `list_id`, issued by `issuer_id` and signed with `issuer`
+fn check_list(list: Credential, list_id: &Url, issuer_id: &str, issuer: &IotaDocument) -> ListVerdict
+{
+    if list.id.as_ref() != Some(list_id)
+    {
+        return ListVerdict::WrongList
+        {
+            expected: list_id.to_string(),
+            found: list.id.as_ref().map(|id| id.to_string()),
+        };
+    }
+
+    for found in [list.issuer.url().as_str(), issuer.id().as_str()]
+    {
+        if found != issuer_id
+        {
+            return ListVerdict::WrongIssuer { expected: issuer_id.to_owned(), found: found.to_owned() };
+        }
+    }
+
+    if let Err(e) = issuer.verify_data(&list, &VerifierOptions::default())
+    {
+        return ListVerdict::InvalidSignature(e.to_string());
+    }
+
+    match RevocationList2020Credential::try_from(list)
+    {
+        Ok(list) => ListVerdict::Valid(Box::new(list)),
+        Err(e) => ListVerdict::Malformed(e.to_string()),
+    }
+}
+
+
+//Verifies the list credential `list` fetched for `credential`: its id must be the `revocationListCredential`
+//of the credential, its issuer the issuer of the credential, and its proof must verify against `issuer`,
+//the resolved DID document of that issuer. Fails only if `credential` has no valid RevocationList2020 status.
+pub fn verify_list_credential<T>(list: Credential, credential: &Credential<T>, issuer: &IotaDocument) -> Result<ListVerdict>
+{
+    let reference = status_reference(credential)?;
+    Ok(check_list(list, reference.list_credential(), credential.issuer.url().as_str(), issuer))
+}
+
+
+//Parses a publication and checks that it is a list credential with id `list_id`, issued and signed by `issuer`
+fn parse_publication(publication: &Publication, list_id: &Url, issuer: &IotaDocument, order: BitOrder) -> Result<RevocationList2020Credential>
+{
+    let credential: Credential = decode_credential(&publication.payload)?;
+    Ok(check_list(credential, list_id, issuer.id().as_str(), issuer).into_list()?.with_bit_order(order))
+}
+
+
+//Returns the newest valid version of the list credential `list_id` published under `index`.
+//Publications that do not parse, have another id or issuer, or are not signed by `issuer` are skipped.
+//Versions with the same issuance date are ordered by their payload bytes, so every verifier reading
+//the same publications picks the same one regardless of the order the source returns them in.
+//The list is read with `order`: `BitOrder::Msb0` as the spec requires, `BitOrder::Lsb0` for legacy lists.
+pub async fn resolve_latest_list<S>(index: &str, list_id: &Url, issuer: &IotaDocument, order: BitOrder, source: &S) -> Result<RevocationList2020Credential>
+where
+    S: StatusListSource + ?Sized,
+{
+    latest_of(source.fetch(index).await?, index, list_id, issuer, order)
+}
+
+//La versione più recente e valida tra le `publications` lette sotto `index`, vedi `resolve_latest_list`
+fn latest_of(publications: Vec<Publication>, index: &str, list_id: &Url, issuer: &IotaDocument, order: BitOrder) -> Result<RevocationList2020Credential>
+{
+    let mut latest: Option<Candidate> = None;
+
+    for publication in publications
+    {
+        let list = match parse_publication(&publication, list_id, issuer, order)
+        {
+            Ok(list) => list,
+            Err(_) => continue,
+        };
+
+        let newer = match &latest
+        {
+            None => true,
+            Some(current) => (list.issuance_date(), &publication.payload) > (current.list.issuance_date(), &current.payload),
+        };
+        if newer
+        {
+            latest = Some(Candidate { list, payload: publication.payload });
+        }
+    }
+
+    latest
+        .map(|candidate| candidate.list)
+        .ok_or_else(|| CredentialError::ListNotFound { index: index.to_owned(), list_id: list_id.to_string() })
+}
+
+
+
+//Versione della lista usata per rispondere
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListVersion
+{
+    pub list_id: Url,
+    pub issuance_date: Timestamp,
+}
+
+//Stato di revoca di una credenziale
+#[derive(Debug)]
+pub enum StatusVerdict
+{
+    //The bit of the credential is not set in the latest valid list
+    Valid { list: ListVersion },
+
+    //The bit of the credential is set in the latest valid list
+    Revoked { list: ListVersion },
+
+    //The status could not be determined: no status, issuer or valid list was found
+    Unknown { reason: CredentialError },
+}
+
+impl StatusVerdict
+{
+    pub fn is_revoked(&self) -> bool
+    {
+        matches!(self, StatusVerdict::Revoked { .. })
+    }
+}
+
+
+//Returns the ledger index the list of `credential` is published under:
+//the `LIST_INDEX_PROPERTY` of the credential if present, the list credential URL otherwise
+pub fn list_index(credential: &Credential, list_id: &Url) -> String
+{
+    match credential.properties.get(LIST_INDEX_PROPERTY)
+    {
+        Some(Value::String(index)) => index.clone(),
+        _ => list_id.to_string(),
+    }
+}
+
+
+//Checks whether `credential` has been revoked: extracts its RevocationList2020 status, resolves the issuer
+//document, fetches the newest valid list from `source`, verifies it against the credential and reads its bit
+//with `order`. Every failure along the way yields `Unknown` with the reason, never an error.
+pub async fn check_status<R, S>(credential: &Credential, resolver: &R, source: &S, order: BitOrder) -> StatusVerdict
+where
+    R: IssuerResolver + ?Sized,
+    S: StatusListSource + ?Sized,
+{
+    match check(credential, resolver, source, order).await
+    {
+        Ok((true, list)) => StatusVerdict::Revoked { list },
+        Ok((false, list)) => StatusVerdict::Valid { list },
+        Err(reason) => StatusVerdict::Unknown { reason },
+    }
+}
+
+async fn check<R, S>(credential: &Credential, resolver: &R, source: &S, order: BitOrder) -> Result<(bool, ListVersion)>
+where
+    R: IssuerResolver + ?Sized,
+    S: StatusListSource + ?Sized,
+{
+    let reference = status_reference(credential)?;
+    let index = list_index(credential, reference.list_credential());
+    let (list, _) = fetch_verified_list(&index, reference.list_credential(), credential.issuer.url().as_str(), order, resolver, source).await?;
+    read_status(&reference, &list)
+}
+
+
+//Resolves `issuer_did`, then returns the newest list `list_id` published under `index` that passes every check,
+//together with the ids of the publications it was chosen from.
+//Each version is verified against the resolved document, which must therefore be that of `issuer_did`.
+pub(crate) async fn fetch_verified_list<R, S>(index: &str, list_id: &Url, issuer_did: &str, order: BitOrder, resolver: &R, source: &S) -> Result<(RevocationList2020Credential, Vec<String>)>
+where
+    R: IssuerResolver + ?Sized,
+    S: StatusListSource + ?Sized,
+{
+    let issuer = resolver.resolve_issuer(issuer_did).await?;
+    if issuer.id().as_str() != issuer_did
+    {
+        return Err(CredentialError::IssuerResolution(format!("resolved '{}' for issuer '{}'", issuer.id(), issuer_did)));
+    }
+    let publications = source.fetch(index).await?;
+    let ids = publications.iter().map(|publication| publication.id.clone()).collect();
+    Ok((latest_of(publications, index, list_id, &issuer, order)?, ids))
+}
+
+//Reads the bit of the credential referenced by `reference` in a verified list
+pub(crate) fn read_status(reference: &StatusReference, list: &RevocationList2020Credential) -> Result<(bool, ListVersion)>
+{
+    let version = ListVersion { list_id: reference.list_credential().clone(), issuance_date: list.issuance_date() };
+    Ok((list.list().is_revoked(reference.index())?, version))
+}
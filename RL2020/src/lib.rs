@@ -1,9 +1,27 @@
 #![allow(dead_code, unused)]
 #![allow(non_snake_case)]
 
+pub mod Bitstring;
+pub mod CredentialStatus;
+pub mod Envelope;
+pub mod IndexAllocator;
+pub mod Inspect;
+pub mod IssuerStore;
+pub mod Ledger;
+pub mod ListCache;
+pub mod ListManager;
+pub mod BitstringStatusList;
+pub mod BitstringStatusListEntry;
+pub mod RevocationList2020Credential;
 pub mod RevocationList2020Status;
+pub mod Snapshot;
+pub mod StatusList2021;
+pub mod StatusList2021Entry;
+pub mod Verifier;
 
 use RevocationList2020Status::*;
+use Bitstring::Codec;
+use Bitstring::EncodedList;
 
 use base64::{decode_config, encode_config, STANDARD};
 use std::fmt::{Display, Formatter};
@@ -12,20 +30,15 @@ use std::io::Read;
 use std::io::prelude::*;
 use serde_derive::{Deserialize, Serialize};
 use std::vec::Vec;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use dataurl::DataUrl;
-use flate2::write::ZlibDecoder as OtherZlibDecoder;
 use flate2::read::ZlibDecoder;
-use flate2::write::ZlibEncoder;
-use flate2::Compression;
+use identity_core::common::Timestamp;
 use identity_core::common::Url;
-use identity_core::utils::Base;
-use identity_core::utils::BaseEncoding;
 
 use identity_did::did::DID;
-use identity_did::error::Error;
-use identity_did::error::Result;
 use identity_did::service::Service;
 use identity_did::service::ServiceEndpoint;
 
@@ -36,51 +49,225 @@ pub const REVOCATION_LIST_2020_TYPE: &str = "RevocationList2020";
 const REVOCATION_LIST_2020_STATUS_TYPE: &str = "RevocationList2020Status";
 
 //Minimum bitstring size is 16kb
-const MIN_BITSTRING_SIZE_KN: usize = 16;
+pub(crate) const MIN_BITSTRING_SIZE_KN: usize = 16;
 
 //Maximum bistsring size is 128kb
-const MAX_BITSTRING_SIZE_KB: usize = 128;
+pub(crate) const MAX_BITSTRING_SIZE_KB: usize = 128;
 
 
 
 
 //CredentialError
 
+//Fase della codifica/decodifica della bitstring in cui si è verificato l'errore
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodingStage 
+{
+    Base64,
+    Zlib,
+    Gzip,
+    Multibase,
+    Snapshot,
+}
+
+impl Display for CodingStage 
+{
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result 
+    {
+        match self 
+        {
+            CodingStage::Base64 => write!(f, "base64"),
+            CodingStage::Zlib => write!(f, "zlib"),
+            CodingStage::Gzip => write!(f, "gzip"),
+            CodingStage::Multibase => write!(f, "multibase"),
+            CodingStage::Snapshot => write!(f, "snapshot"),
+        }
+    }
+}
+
+
+//Errors returned by the revocation list, its status entries and the code fetching/publishing them
 #[derive(Debug)]
-pub struct CredentialError 
+pub enum CredentialError 
 {
-    message: String,
+    //The index is not addressable in a list of the given capacity
+    IndexOutOfRange { index: u64, capacity: u64 },
+
+    //The index cannot be converted to a position on this platform
+    IndexNotAddressable { index: u64 },
+
+    //The requested bitstring size (kb) is outside the allowed bounds
+    InvalidSize { size: usize, min: usize, max: usize },
+
+    //The revocation list id is empty
+    EmptyId,
+
+    //Decoding the encoded list failed at the given stage
+    Decoding { stage: CodingStage, message: String },
+
+    //Encoding the bitstring failed at the given stage
+    Encoding { stage: CodingStage, message: String },
+
+    //The `credentialStatus` entry is malformed
+    InvalidStatus(String),
+
+    //The credential has no `credentialStatus` entry of the expected type
+    MissingStatus,
+
+    //The list credential does not have the RevocationList2020Credential structure
+    InvalidListCredential(String),
+
+    //Status entries must be between 1 and 64 bits wide
+    InvalidStatusSize(u8),
+
+    //The value does not fit in an entry of `status_size` bits
+    StatusValueOutOfRange { value: u64, status_size: u8 },
+
+    //The bitstring holds fewer entries than the spec minimum
+    TooFewEntries { entries: usize, min: usize },
+
+    //Publishing the list credential to the ledger failed
+    Publish(String),
+
+    //Reading the published list credentials from the ledger failed
+    Fetch(String),
+
+    //No valid version of the list credential was published under the index
+    ListNotFound { index: String, list_id: String },
+
+    //The ledger payload is not a well-formed envelope
+    InvalidEnvelope(String),
+
+    //The DID document of the issuer could not be resolved
+    IssuerResolution(String),
+
+    //The index was already assigned to another credential
+    IndexAlreadyAllocated(u64),
+
+    //Every index of the list is allocated, a new list must be opened
+    ListFull { list_id: String, capacity: u64 },
+
+    //Reading or writing the issuer's persistent state failed
+    Storage(String),
+
+    //No index was allocated to the credential
+    UnknownCredential(String),
+
+    //An index was already allocated to the credential
+    CredentialAlreadyAllocated(String),
+
+    //The two lists have different ids, so they are not versions of the same list
+    ListIdMismatch { expected: String, found: String },
+
+    //The two lists have a different number of entries
+    ListSizeMismatch { expected: u64, found: u64 },
 }
 
 impl CredentialError 
 {
-    pub fn new(msg: &str) -> Self 
-	{
-        CredentialError 
-		{
-            message: String::from(msg),
-        }
+    pub(crate) fn decoding(stage: CodingStage, err: impl Display) -> Self 
+    {
+        CredentialError::Decoding { stage, message: err.to_string() }
+    }
+
+    pub(crate) fn encoding(stage: CodingStage, err: impl Display) -> Self 
+    {
+        CredentialError::Encoding { stage, message: err.to_string() }
     }
 }
 
 impl Display for CredentialError 
 {
-    // This trait requires `fmt` with this exact signature.
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        // Write strictly the first element into the supplied output
-        // stream: `f`. Returns `fmt::Result` which indicates whether the
-        // operation succeeded or failed. Note that `write!` uses syntax which
-        // is very similar to `println!`.
-        write!(f, "{}", self.message)
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result 
+    {
+        match self 
+        {
+            CredentialError::IndexOutOfRange { index, capacity } => write!(
+                f,
+                "max indexable element is {}, provided index {} is out of range",
+                capacity, index
+            ),
+            CredentialError::IndexNotAddressable { index } => write!(
+                f,
+                "index {} cannot be addressed on this platform",
+                index
+            ),
+            CredentialError::InvalidSize { size, min, max } => write!(
+                f,
+                "bitstring size must be between {} and {} kb, got {}",
+                min, max, size
+            ),
+            CredentialError::EmptyId => write!(f, "Revocation list id cannot be empty"),
+            CredentialError::Decoding { stage, message } => write!(f, "{} decoding failed: {}", stage, message),
+            CredentialError::Encoding { stage, message } => write!(f, "{} encoding failed: {}", stage, message),
+            CredentialError::InvalidStatus(message) => write!(f, "invalid credential status: {}", message),
+            CredentialError::MissingStatus => write!(f, "credential has no RevocationList2020 status"),
+            CredentialError::InvalidListCredential(message) => write!(f, "invalid list credential: {}", message),
+            CredentialError::InvalidStatusSize(size) => write!(f, "status size must be between 1 and 64 bits, got {}", size),
+            CredentialError::StatusValueOutOfRange { value, status_size } => write!(
+                f,
+                "status value {} does not fit in {} bits",
+                value, status_size
+            ),
+            CredentialError::TooFewEntries { entries, min } => write!(
+                f,
+                "status list must hold at least {} entries, got {}",
+                min, entries
+            ),
+            CredentialError::Publish(message) => write!(f, "publishing the status list failed: {}", message),
+            CredentialError::Fetch(message) => write!(f, "fetching the status list failed: {}", message),
+            CredentialError::ListNotFound { index, list_id } => write!(
+                f,
+                "no valid version of list credential '{}' found under index '{}'",
+                list_id, index
+            ),
+            CredentialError::InvalidEnvelope(message) => write!(f, "invalid payload envelope: {}", message),
+            CredentialError::IssuerResolution(message) => write!(f, "resolving the issuer failed: {}", message),
+            CredentialError::IndexAlreadyAllocated(index) => write!(f, "index {} is already allocated", index),
+            CredentialError::ListFull { list_id, capacity } => write!(
+                f,
+                "all {} indices of list '{}' are allocated",
+                capacity, list_id
+            ),
+            CredentialError::Storage(message) => write!(f, "storage error: {}", message),
+            CredentialError::UnknownCredential(id) => write!(f, "no index allocated to credential '{}'", id),
+            CredentialError::CredentialAlreadyAllocated(id) => write!(f, "an index is already allocated to credential '{}'", id),
+            CredentialError::ListIdMismatch { expected, found } => write!(
+                f,
+                "list '{}' is not a version of list '{}'",
+                found, expected
+            ),
+            CredentialError::ListSizeMismatch { expected, found } => write!(
+                f,
+                "list has {} entries, expected {}",
+                found, expected
+            ),
+        }
     }
 }
 
+impl std::error::Error for CredentialError {}
+
+//Conversione esplicita di un indice in posizione, al posto dei cast `as usize`
+pub(crate) fn index_to_usize(index: u64) -> Result<usize>
+{
+    usize::try_from(index).map_err(|_| CredentialError::IndexNotAddressable { index })
+}
+
+//Capacità espressa nello stesso tipo degli indici
+pub(crate) fn capacity_to_u64(capacity: usize) -> u64
+{
+    u64::try_from(capacity).unwrap_or(u64::MAX)
+}
+
+pub type Result<T, E = CredentialError> = std::result::Result<T, E>;
+
 
 
 
 //RevocationStatus
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RevocationStatus 
 {
     Revoke,
@@ -89,26 +276,94 @@ pub enum RevocationStatus
 
 
 
+//BitOrder
+
+//Ordine dei bit all'interno di ogni byte della bitstring.
+//La specifica indicizza i bit partendo dal più significativo (index 0 = bit più a sinistra),
+//le liste pubblicate prima di questa correzione usano invece il bit meno significativo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BitOrder 
+{
+    //Spec-conformant: index 0 is the leftmost bit of the first byte
+    #[default]
+    Msb0,
+
+    //Legacy: index 0 is the rightmost bit of the first byte
+    Lsb0,
+}
+
+impl BitOrder 
+{
+    //Returns the mask selecting the bit at `offset` (0..8) inside a byte
+    pub fn mask(&self, offset: u8) -> u8 
+    {
+        match self 
+        {
+            BitOrder::Msb0 => 0x80 >> offset,
+            BitOrder::Lsb0 => 1 << offset,
+        }
+    }
+}
+
+
+
+
+
+
+
+
+//Diff
+
+//Indici che cambiano stato passando da una versione della lista a un'altra
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ListDiff
+{
+    //Revoked in the other version only
+    pub added: Vec<u64>,
+
+    //Revoked in this version only, reinstated in the other
+    pub removed: Vec<u64>,
+}
+
+impl ListDiff
+{
+    pub fn is_empty(&self) -> bool
+    {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+
+//MergePolicy
+
+//Come riconciliare due copie divergenti della stessa lista
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy
+{
+    //An index is revoked if it is revoked in either copy: a revocation is never undone by a merge
+    Union,
+
+    //Every index takes its state from the copy issued last; copies issued at the same time are merged as `Union`
+    PreferNewer { ours: Timestamp, theirs: Timestamp },
+}
+
+
+
 
 
 
 
 
 //A revocation list for managing credential revocation.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+//Con serde la lista passa dallo snapshot versionato, vedi Snapshot.rs.
+#[derive(Debug, Clone)]
 pub struct RevocationList2020 
 {
-    #[serde(rename = "id")]
     id: String,
 	
-    #[serde(rename = "type")]
     typ: String,
     
-	#[serde(rename = "encodedList")]
-    encoded_list: String,
-    
-	#[serde(rename = "bitSet")]
-    bit_set: Vec<u8>,
+    list: EncodedList,
 }
 
 
@@ -118,60 +373,57 @@ impl RevocationList2020
     //The name of the service type
     pub const TYPE: &'static str = "RevocationList2020";
 
-    pub fn get_encList(&self) -> String
+    pub fn id(&self) -> &str
     {
-        self.encoded_list.clone()
+        &self.id
     }
 
-  
-    fn pack(data: &Vec<u8>) -> Result<String, CredentialError> 
-	{
-        //compress the data
-        let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
-		
-        e.write_all(data).map_err(|e| CredentialError::new(&e.to_string()))?;
-		
-        let compressed = e.finish().map_err(|e| CredentialError::new(&e.to_string()))?;
-		
-        //encode the data
-        Ok(encode_config(&compressed, STANDARD))
+    //Returns the encoded list. If the bitstring changed since the last `encode()`
+    //the value is recomputed without being cached.
+    pub fn get_encList(&self) -> Result<String>
+    {
+        self.list.get_encList()
     }
 
+    //Compresses and encodes the bitstring if it changed since the last call and caches the result.
+    //Bulk updates should be followed by a single call to this method.
+    pub fn encode(&mut self) -> Result<&str>
+    {
+        self.list.encode()
+    }
 
-    fn unpack(data: &String) -> Result<Vec<u8>, CredentialError> 
-	{
-        let bin = decode_config(&data, STANDARD).map_err(|e| CredentialError::new(&e.to_string()))?;
-        let mut d = ZlibDecoder::new(&*bin);
-        let mut buf = Vec::new();
-        d.read_to_end(&mut buf).map_err(|e| CredentialError::new(&e.to_string()))?;
-        Ok(buf)
+    //Returns `true` if the cached encoded list is out of date
+    pub fn is_dirty(&self) -> bool
+    {
+        self.list.is_dirty()
     }
 
+    //Returns the bit order used to address entries of the bitstring
+    pub fn bit_order(&self) -> BitOrder
+    {
+        self.list.bits().order()
+    }
 
-    fn check_bounds(&self, index: u64) -> Result<(), CredentialError> 
-	{
-        match index 
-		{
-            i if (i as usize) >= self.capacity() => Err(CredentialError::new(&format!(
-                "max indexable element is {}, provided index {} is out of range",
-                self.capacity(),
-                i,
-            ))),
-            _ => Ok(()),
-        }
+    //Sets the bit order used to address entries, e.g. `BitOrder::Lsb0` to read lists
+    //published on the Tangle before the spec-conformant ordering was introduced.
+    //The bitstring itself is left untouched.
+    pub fn with_bit_order(mut self, order: BitOrder) -> Self
+    {
+        self.list.set_order(order);
+        self
     }
-	
-	
+
+
 	//Returns numero di entry/bit disponibili, 1024 x 16 x 8 (bit per cella)
     pub fn capacity(&self) -> usize 
 	{
-        self.bit_set.len() * 8
+        self.list.bits().capacity()
     }
 
     //Size returns the size of the bitset int kb
     pub fn size(&self) -> usize 
 	{
-        return self.bit_set.len() / 1024;
+        self.list.bits().byte_len() / 1024
     }
 	
 
@@ -179,67 +431,53 @@ impl RevocationList2020
     // Constructs a new empty [`RevocationList2020`].
     pub fn new(id: &str, size: usize) -> Result<Self, CredentialError> 
 	{
-        if size < MIN_BITSTRING_SIZE_KN 
-		{
-            return Err(CredentialError::new(&format!(
-                "Minimum credential size is {}, got {}",
-                MIN_BITSTRING_SIZE_KN, size
-            )));
-        }
-		
-        if size > MAX_BITSTRING_SIZE_KB 
+        if !(MIN_BITSTRING_SIZE_KN..=MAX_BITSTRING_SIZE_KB).contains(&size) 
 		{
-            return Err(CredentialError::new(&format!(
-                "Maximum credential size is {}, got {}",
-                MIN_BITSTRING_SIZE_KN, size
-            )));
+            return Err(CredentialError::InvalidSize 
+            {
+                size,
+                min: MIN_BITSTRING_SIZE_KN,
+                max: MAX_BITSTRING_SIZE_KB,
+            });
         }
 		
         if id.trim().is_empty() 
 		{
-            return Err(CredentialError::new("Revocation list id cannot be empty"));
+            return Err(CredentialError::EmptyId);
         }
 		
 		
         //initialize the bitset
-        let bs = vec![0; size * 1024];
-        let el = Self::pack(&bs)?;
+        let bs = Bitstring::Bitstring::new(size * 1024);
 
         Ok(RevocationList2020 
 		    {
             id: String::from(id),
             typ: String::from(REVOCATION_LIST_2020_TYPE),
-            encoded_list: el,
-            bit_set: bs,
+            list: EncodedList::new(bs, Codec::ZlibBase64)?,
             })
 			
     }
 
 
     //Crea lista settando la encodedList
-    pub fn new_withList(id: &str, encList: &String) -> Result<Self, CredentialError> 
+    pub fn new_withList(id: &str, encList: &str) -> Result<Self, CredentialError> 
 	{
-		let el = encList.clone();
-
         if id.trim().is_empty() 
 		{
-            return Err(CredentialError::new("Revocation list id cannot be empty"));
+            return Err(CredentialError::EmptyId);
         }
 		
 		
         //initialize the bitset
-        let bin = decode_config(&encList, STANDARD).map_err(|e| CredentialError::new(&e.to_string()))?;
-        let mut d = ZlibDecoder::new(&*bin);
-        let mut buf = Vec::new();
-        d.read_to_end(&mut buf).map_err(|e| CredentialError::new(&e.to_string()))?;
-        
+        let buf = EncodedList::unpack(encList, Codec::ZlibBase64)?;
+
 
         Ok(RevocationList2020 
 		    {
             id: String::from(id),
             typ: String::from(REVOCATION_LIST_2020_TYPE),
-            encoded_list: el,
-            bit_set: buf,
+            list: buf,
             })
 			
     }
@@ -274,104 +512,153 @@ impl RevocationList2020
 	//Cambio un bit nella lista
 	pub fn update(&mut self, action: RevocationStatus, index: u64) -> Result<(), CredentialError> 
 	{
-        self.check_bounds(index)?;
-
-        let pos = (index / 8) as usize;
-        let j = (index % 8) as u8;
-
-        match action 
-		{
-            RevocationStatus::Revoke => self.bit_set[pos] |= 1 << j,
-            RevocationStatus::Reset => self.bit_set[pos] &= !(1 << j),
-        };
-		
-        self.encoded_list = Self::pack(&self.bit_set)?;
+        self.list.set(index, action == RevocationStatus::Revoke)?;
         Ok(())
     }
 
 
-	//Ottengo lo status del bit in quella posizione
-    pub fn get(&self, index: u64) -> Result<RevocationStatus, CredentialError> 
-	{
-        self.check_bounds(index)?;
-
-        let pos = (index / 8) as usize;
-        let j = (index % 8) as u8;
-
-        match self.bit_set[pos] & (1 << j) 
-		{
-            0 => Ok(RevocationStatus::Reset),
-            _ => Ok(RevocationStatus::Revoke),
-        }
+    //Revokes every index of `indices`. Returns the indices whose state changed.
+    pub fn revoke_many<I>(&mut self, indices: I) -> Result<Vec<u64>>
+    where
+    I: IntoIterator<Item = u64>,
+    {
+        self.apply(indices.into_iter().map(|i| (i, RevocationStatus::Revoke)))
     }
-	  
 
-  
-    //DESERIALIZZAZIONE
-  
-    //Deserializes a compressed [`RevocationList2020`] base64-encoded `data`.
-    pub(crate) fn deserialize_compressed_base64<T>(data: &T) -> Result<Self>
+
+    //Resets every index of `indices`. Returns the indices whose state changed.
+    pub fn reset_many<I>(&mut self, indices: I) -> Result<Vec<u64>>
     where
-    T: AsRef<str> + ?Sized,
+    I: IntoIterator<Item = u64>,
     {
-        let decoded_data: Vec<u8> = BaseEncoding::decode(data, Base::Base64Url)
-        .map_err(|e| Error::Base64DecodingError(data.as_ref().to_owned(), e))?;
-        let decompressed_data: Vec<u8> = Self::decompress_zlib(decoded_data)?;
-        Self::deserialize_slice(&decompressed_data)
+        self.apply(indices.into_iter().map(|i| (i, RevocationStatus::Reset)))
     }
 
-    //Deserializes [`RevocationList`] from a slice of bytes.
-    fn deserialize_slice(data: &[u8]) -> Result<Self> 
+
+    //Applies all `changes` in order. Bounds are checked before any bit is touched, so on error
+    //the list is left unmodified. Returns, sorted, the indices whose final state differs from the initial one.
+    pub fn apply<I>(&mut self, changes: I) -> Result<Vec<u64>>
+    where
+    I: IntoIterator<Item = (u64, RevocationStatus)>,
     {
-        let decoded : RevocationList2020 = bincode::deserialize(data).unwrap();
-        Ok(decoded)
+        self.list.apply(
+            changes
+                .into_iter()
+                .map(|(index, action)| (index, action == RevocationStatus::Revoke))
+                .collect(),
+        )
     }
 
 
-
-    //SERIALIZZAZIONE
+	//Ottengo lo status del bit in quella posizione
+    pub fn get(&self, index: u64) -> Result<RevocationStatus, CredentialError> 
+	{
+        match self.list.bits().get(index)? 
+		{
+            false => Ok(RevocationStatus::Reset),
+            true => Ok(RevocationStatus::Revoke),
+        }
+    }
 
 
-    //Serializes and compressess [`RevocationList`] as a base64-encoded `String`.
-    //Qui avviene l'encoding
-    pub(crate) fn serialize_compressed_base64(&self) -> Result<String> 
+    //Returns the indices whose state differs in `other`, a version of the same list.
+    //Fails if the lists have different ids or sizes.
+    pub fn diff(&self, other: &RevocationList2020) -> Result<ListDiff>
     {
-        let serialized_data: Vec<u8> = self.serialize_vec()?;
-        Self::compress_zlib(&serialized_data).map(|data| BaseEncoding::encode(&data, Base::Base64Url))
+        self.check_compatible(other)?;
+
+        //Confronto ordinato degli indici revocati nelle due versioni
+        let mut diff = ListDiff::default();
+        let mut ours = self.iter_revoked().peekable();
+        let mut theirs = other.iter_revoked().peekable();
+        loop
+        {
+            match (ours.peek().copied(), theirs.peek().copied())
+            {
+                (None, None) => break,
+                (Some(a), Some(b)) if a == b =>
+                {
+                    ours.next();
+                    theirs.next();
+                }
+                (Some(a), b) if b.is_none_or(|b| a < b) =>
+                {
+                    diff.removed.push(a);
+                    ours.next();
+                }
+                (_, Some(b)) =>
+                {
+                    diff.added.push(b);
+                    theirs.next();
+                }
+                (Some(_), None) => unreachable!("handled by the previous arms"),
+            }
+        }
+        Ok(diff)
     }
 
+    //Merges `other`, a divergent copy of the same list, into this one according to `policy`.
+    //Fails if the lists have different ids or sizes. Returns, sorted, the indices whose state changed.
+    pub fn merge(&mut self, other: &RevocationList2020, policy: MergePolicy) -> Result<Vec<u64>>
+    {
+        let diff = self.diff(other)?;
+        match policy
+        {
+            MergePolicy::PreferNewer { ours, theirs } if ours > theirs => Ok(Vec::new()),
+            MergePolicy::PreferNewer { ours, theirs } if ours < theirs => self.apply(
+                diff.added
+                    .into_iter()
+                    .map(|index| (index, RevocationStatus::Revoke))
+                    .chain(diff.removed.into_iter().map(|index| (index, RevocationStatus::Reset))),
+            ),
+            _ => self.revoke_many(diff.added),
+        }
+    }
 
-    //Serializes a [`RevocationList`] as a vector of bytes.
-    //Devo trasformare la lista in un vettore di bytes per poterla comprimere
-    fn serialize_vec(&self) -> Result<Vec<u8>> 
+    fn check_compatible(&self, other: &RevocationList2020) -> Result<()>
     {
-        let mut output: Vec<u8> = Vec::with_capacity(self.capacity());
-        
-        //Trasformazione struct in bytes
-        output = bincode::serialize(self).unwrap();
-        
-        Ok(output)
+        if self.id != other.id
+        {
+            return Err(CredentialError::ListIdMismatch { expected: self.id.clone(), found: other.id.clone() });
+        }
+        if self.capacity() != other.capacity()
+        {
+            return Err(CredentialError::ListSizeMismatch
+            {
+                expected: capacity_to_u64(self.capacity()),
+                found: capacity_to_u64(other.capacity()),
+            });
+        }
+        Ok(())
     }
 
 
+    //Iterates the revoked indices in ascending order, reading the bitstring a word at a time
+    pub fn iter_revoked(&self) -> impl Iterator<Item = u64> + '_
+    {
+        self.list.bits().ones()
+    }
 
-    //Algoritmi di compressione/decompressione e tryFrom per passare da service a RevocationList
+    //Iterates the runs of consecutive revoked indices as closed ranges [start, end]
+    pub fn iter_set_ranges(&self) -> impl Iterator<Item = (u64, u64)> + '_
+    {
+        self.list.bits().one_ranges()
+    }
 
-    fn compress_zlib<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>> 
+    //Returns the number of revoked indices
+    pub fn count_revoked(&self) -> u64
     {
-        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
-        encoder.write_all(input.as_ref()).map_err(Error::BitmapEncodingError)?;
-        encoder.finish().map_err(Error::BitmapEncodingError)
+        self.list.bits().count_ones()
     }
 
-    fn decompress_zlib<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>> 
+    //Iterates the status of every index, from 0 to `capacity() - 1`
+    pub fn iter(&self) -> impl Iterator<Item = (u64, RevocationStatus)> + '_
     {
-        let mut writer = Vec::new();
-        let mut decoder = OtherZlibDecoder::new(writer);
-        decoder.write_all(input.as_ref()).map_err(Error::BitmapDecodingError)?;
-        writer = decoder.finish().map_err(Error::BitmapDecodingError)?;
-        Ok(writer)
+        (0..).zip(self.list.bits().iter()).map(|(index, revoked)| match revoked
+        {
+            true => (index, RevocationStatus::Revoke),
+            false => (index, RevocationStatus::Reset),
+        })
     }
-	
 }
 
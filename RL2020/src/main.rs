@@ -2,387 +2,480 @@
 #![allow(non_snake_case)]
 
 
-use std::str;
-use std::str::FromStr;
+use std::ffi::OsString;
+use std::fs;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
+use std::process::ExitCode;
 
-use identity_iota::account_storage::Stronghold;
-use identity_iota::account_storage::Storage;
-
-use iota_client::{Client as ClientDev, Result as ClientResult};
-use bee_message::prelude::MessageId;
-use bee_common::packable::Packable;
-
-use identity_iota::client::Client;
-use identity_iota::client::ExplorerUrl;
-use identity_iota::client::ResolvedIotaDocument;
-use identity_iota::client::Resolver;
-use identity_iota::client::CredentialValidationOptions;
-use identity_iota::client::CredentialValidator;
-use identity_iota::client::FailFast;
-use identity_iota::client::ValidationError;
+use clap::Args;
+use clap::Parser;
+use clap::Subcommand;
 
 use identity_iota::account::Account;
-use identity_iota::account::AccountBuilder;
-use identity_iota::account::Result;
-use identity_iota::account::IdentitySetup;
-use identity_iota::account::MethodContent;
-use identity_iota::did::DID;
-use identity_iota::iota_core::IotaDID;
-use identity_iota::core::Value;
-use identity_iota::core::Timestamp;
-use identity_iota::core::Url;
-use identity_iota::core::json;
+use identity_iota::account_storage::Stronghold;
+use identity_iota::client::Resolver;
 use identity_iota::core::FromJson;
 use identity_iota::core::ToJson;
-use identity_iota::credential::Status;
+use identity_iota::core::Url;
 use identity_iota::credential::Credential;
-use identity_iota::credential::CredentialBuilder;
-use identity_iota::credential::Subject;
-use identity_iota::crypto::ProofOptions;
+use identity_iota::did::DID;
+use identity_iota::iota_core::IotaDID;
+use identity_iota::iota_core::IotaDocument;
 
-use RevocationList2020;
-use RevocationList2020::RevocationList2020Status;
+use ::RevocationList2020::BitOrder;
+use ::RevocationList2020::RevocationList2020;
+use ::RevocationList2020::Envelope::{encode_credential, Compression};
+use ::RevocationList2020::Inspect::inspect;
+use ::RevocationList2020::IssuerStore::{decode_record, encode_record, ListRecord};
+use ::RevocationList2020::Ledger::{FileLedger, IotaIndexation, StatusListPublisher, StatusListSource};
+use ::RevocationList2020::ListManager::{AccountSigner, ListSigner};
+use ::RevocationList2020::Verifier::{check_status, StatusVerdict};
 
 
-#[tokio::main]
-async fn main() -> Result<()> 
+type CliResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+//Exit code di `credential check`: 0 valida, 1 revocata, 2 stato sconosciuto o errore
+const EXIT_REVOKED: u8 = 1;
+const EXIT_ERROR: u8 = 2;
+
+
+
+#[derive(Parser)]
+#[command(name = "RL2020", version, about = "Create, update, publish and check RevocationList2020 status lists")]
+struct Cli
+{
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command
+{
+  #[command(subcommand, about = "Manage list files")]
+  List(ListCommand),
+
+  #[command(subcommand, about = "Check credentials against their published list")]
+  Credential(CredentialCommand),
+}
+
+
+//File di stato della lista: se manca la lista viene letta da stdin e riscritta su stdout
+#[derive(Args)]
+struct ListFile
+{
+  #[arg(short, long, value_name = "FILE", help = "List file, read from stdin and written to stdout when omitted")]
+  list: Option<PathBuf>,
+}
+
+//Nessun nodo predefinito: va indicato il nodo IOTA o una directory locale
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct LedgerArgs
+{
+  #[arg(long, value_name = "URL", help = "IOTA node the lists are published on")]
+  node: Option<String>,
+
+  #[arg(long, value_name = "DIR", help = "Use a ledger directory instead of the IOTA node")]
+  ledger: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct AccountArgs
+{
+  #[arg(long, value_name = "DID", help = "DID of the issuer")]
+  did: String,
+
+  #[arg(long, value_name = "FILE", default_value = "./stronghold_file.hold", help = "Stronghold snapshot holding the issuer keys")]
+  stronghold: PathBuf,
+
+  #[arg(long, env = "RL2020_STRONGHOLD_PASSWORD", hide_env_values = true, help = "Password of the stronghold snapshot")]
+  password: String,
+
+  #[arg(long, default_value = "#key-1", help = "Verification method signing the list credential")]
+  fragment: String,
+}
+
+
+#[derive(Subcommand)]
+enum ListCommand
+{
+  #[command(about = "Create an empty list")]
+  Create
+  {
+    #[arg(long, value_name = "URL", help = "Id of the list credential")]
+    id: String,
+
+    #[arg(long, value_name = "KB", default_value_t = 16, help = "Size of the bitstring in kb")]
+    size: usize,
+
+    #[arg(long, help = "Address the bits of each byte from the least significant one (legacy lists)")]
+    lsb0: bool,
+
+    #[command(flatten)]
+    file: ListFile,
+  },
+
+  #[command(about = "Revoke the credentials at the given indices")]
+  Revoke
+  {
+    #[command(flatten)]
+    file: ListFile,
+
+    #[arg(required = true, value_name = "INDEX")]
+    indices: Vec<u64>,
+  },
+
+  #[command(about = "Reinstate the credentials at the given indices")]
+  Reset
+  {
+    #[command(flatten)]
+    file: ListFile,
+
+    #[arg(required = true, value_name = "INDEX")]
+    indices: Vec<u64>,
+  },
+
+  #[command(about = "Print whether the given indices are revoked")]
+  Status
+  {
+    #[command(flatten)]
+    file: ListFile,
+
+    #[arg(required = true, value_name = "INDEX")]
+    indices: Vec<u64>,
+  },
+
+  #[command(about = "Print the encodedList of the list, or its unsigned list credential")]
+  Encode
+  {
+    #[command(flatten)]
+    file: ListFile,
+
+    #[arg(long, value_name = "DID", help = "Print the unsigned list credential issued by this DID")]
+    issuer: Option<String>,
+  },
+
+  #[command(about = "Create a list file from an encodedList")]
+  Decode
+  {
+    #[arg(long, value_name = "URL", help = "Id of the list credential")]
+    id: String,
+
+    #[arg(long, help = "Address the bits of each byte from the least significant one (legacy lists)")]
+    lsb0: bool,
+
+    #[arg(value_name = "ENCODED", help = "encodedList, read from stdin when omitted")]
+    encoded: Option<String>,
+
+    #[command(flatten)]
+    file: ListFile,
+  },
+
+  #[command(about = "Show size, revoked entries and compression of an encodedList or list credential")]
+  Inspect
+  {
+    #[arg(value_name = "FILE", help = "encodedList or list credential JSON, read from stdin when omitted")]
+    input: Option<PathBuf>,
+
+    #[arg(long, help = "Address the bits of each byte from the least significant one (legacy lists)")]
+    lsb0: bool,
+
+    #[arg(long, help = "Print the report as JSON")]
+    json: bool,
+  },
+
+  #[command(about = "Sign the list credential with the issuer account and publish it")]
+  Publish
+  {
+    #[command(flatten)]
+    file: ListFile,
+
+    #[command(flatten)]
+    account: AccountArgs,
+
+    #[command(flatten)]
+    ledger: LedgerArgs,
+
+    #[arg(long, value_name = "INDEX", help = "Ledger index to publish under, the list id by default")]
+    index: Option<String>,
+  },
+}
+
+
+#[derive(Subcommand)]
+enum CredentialCommand
 {
-  //Gestione DB
-  let stronghold_path: PathBuf = "./stronghold_file.hold".into();
-  let password: String = "password27".to_owned();
-  let stronghold: Stronghold = Stronghold::new(&stronghold_path, password, None).await?;
-
-  //Creo account handler per l'Identity dell'issuer
-  let mut issuer: Account = Account::builder()
-  .storage(stronghold)
-  .load_identity(IotaDID::parse("did:iota:FGZTMJSQZoGJxE416TmFnEjCDRJWFE5uTmEMEm5vBypv")?)
-  .await?;
-
-  ////////////////////////////////////Creazione soggetti e status per le VC revocabili
-  
-  //Indice dei messaggi da pubblicare sulla Tangle
-  let mut index_VC_list = "RL2020_MyList";
-  
-  
-  //Subject 1: Alice
-  let sub1: Subject = Subject::from_json_value(json!({
-    "id": "did:iota:GYR6iDwMrNHDXRMZyKztFf2RQ14jzJC9u1iXTVKPdV9B",
-    "name" : "Alice",
-    "public_key": "zH8P9Nu9zpgZutEFeb2RC5VVFr3V4ZhWspYZx7SGP5TPa",
-  }))?;
-
-
-  //Subject 2: Bob
-  let sub2: Subject = Subject::from_json_value(json!({
-    "id": "did:iota:Cn3xod8v3Yk4WHFiKXxfVzVm5E7T2PWMzRrAWAmWps2",
-    "name": "Bob",
-    "public_key": "zGqGKuMEpAUhpqpJJoPx4e9xWxz4K5zjbyh5DKctk5NbA",
-  }))?;
-
-
-  //Subject 3: Mark
-  let sub3: Subject = Subject::from_json_value(json!({
-    "id": "did:iota:fHiyTrBiGHeszGBiWCXUpasMoY9oM1eqVgQRsH3ZKeW ",
-    "name" : "Mark",
-    "public_key": "zFvZEpKBebFi6yGH4NAtpTT7794row5TNqQPXMkhJQExY",
-  }))?;
-  
-
-  //Status Alice
-  let service_url = issuer.did().clone().to_url();
-  let credential_index: u32 = 7;
-  let status_A : Status = RevocationList2020Status::RevocationList2020Status::new(service_url, credential_index, "https://example.com/credentials/status/3").into();
-
-  //Status Bob
-  let service_url2 = issuer.did().clone().to_url();
-  let credential_index2: u32 = 2500;
-  let status_B : Status = RevocationList2020Status::RevocationList2020Status::new(service_url2, credential_index2, "https://example.com/credentials/status/3").into();
-
-  //Status Mark
-  let service_url3 = issuer.did().clone().to_url();
-  let credential_index3: u32 = 100;
-  let status_M : Status = RevocationList2020Status::RevocationList2020Status::new(service_url3, credential_index3, "https://example.com/credentials/status/3").into();
-
-
-  ///////////////////////////////////////Creazione VC revocabili
-  
-  let mut credential_A: Credential = CredentialBuilder::default()
-  .id(Url::parse("https://example.com/credentials/23894672394")?)
-  .context(Url::parse("https://w3id.org/vc-revocation-list-2020/v1").unwrap())
-  .issuer(Url::parse(issuer.did().clone().as_str())?)
-  .subject(sub1)
-  .status(status_A)
-  .property("IndiceRL", Value::from(index_VC_list))
-  .build()?;
-
-
-  let mut credential_B: Credential = CredentialBuilder::default()
-  .id(Url::parse("https://example.com/credentials/23894672394")?)
-  .context(Url::parse("https://w3id.org/vc-revocation-list-2020/v1").unwrap())
-  .issuer(Url::parse(issuer.did().clone().as_str())?)
-  .subject(sub2)
-  .status(status_B)
-  .property("IndiceRL", Value::from(index_VC_list))
-  .build()?;
-
-
-  let mut credential_M: Credential = CredentialBuilder::default()
-  .id(Url::parse("https://example.com/credentials/23894672394")?)
-  .context(Url::parse("https://w3id.org/vc-revocation-list-2020/v1").unwrap())
-  .issuer(Url::parse(issuer.did().clone().as_str())?)
-  .subject(sub3)
-  .status(status_M)
-  .property("IndiceRL", Value::from(index_VC_list))
-  .build()?;
-
-
-  //Firma delle credenziali
-  issuer
-  .sign("#key-1", &mut credential_A, ProofOptions::default())
-  .await?;
-
-  issuer
-  .sign("#key-1", &mut credential_B, ProofOptions::default())
-  .await?;
-
-  issuer
-  .sign("#key-1", &mut credential_M, ProofOptions::default())
-  .await?;
-
-
-
-
-  ///////////////////////////////////////////////////////////////Generazione VC lista
-
-  //Creo lista di dim minima 16kb
-  let mut rl = RevocationList2020::RevocationList2020::new("https://example.com/credentials/status/3", 16).unwrap();
-  let mut list_coded : String = rl.get_encList();
-
-
-  //Creo soggetto per la VC lista
-  let list_sub: Subject = Subject::from_json_value(json!({
-      "id": issuer.document().id(),
-      "encodedList": list_coded,
-    }))?;
-
-  
-  //Creazione della VC lista
-  let mut lista_VC: Credential = CredentialBuilder::default()
-  .id(Url::parse("https://example.com/credentials/status/3")?)
-  .context(Url::parse("https://w3id.org/vc-revocation-list-2020/v1").unwrap())
-  .issuer(Url::parse(issuer.did().clone().as_str())?)
-  .type_("RevocationList2020Credential")
-  .subject(list_sub)
-  .build()?;
-
-  //Firma della credenziale lista
-  issuer
-  .sign("#key-1", &mut lista_VC, ProofOptions::default())
-  .await?;
-  
-  ///////////////////////////////////Pubblicazione della RevocationList sulla Tangle
-
-  //Creazione client
-  let iota_cl = ClientDev::builder()
-  .with_node("https://api.lb-0.h.chrysalis-devnet.iota.cafe")
-  .unwrap()
-  .finish()
-  .await
-  .unwrap();
-
-
-  //Lista -> payload
-  let string_list = String::from(lista_VC.to_string());
-  let mut pl_credential : Vec<u8> = string_list.into_bytes();
-
-
-  //Pubblicazione sulla Tangle
-  let sent = iota_cl
-    .message()
-    .with_index(index_VC_list)
-    .with_data(pl_credential)
-    .finish()
-    .await;
-
-
-  ///////////////////////////////////////////////////////Revoca delle credenziali
-  
-  //Vettore credenziali da revocare
-  let mut cred_arr : Vec<Credential> = vec![credential_A.clone(), credential_B.clone()];
+  #[command(about = "Check whether a credential is revoked; exits with 0 if valid, 1 if revoked, 2 if unknown")]
+  Check
+  {
+    #[arg(value_name = "FILE", help = "Credential JSON, read from stdin when omitted")]
+    credential: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE", help = "Verify the list with this DID document instead of resolving the issuer")]
+    issuer_document: Option<PathBuf>,
+
+    #[arg(long, help = "Address the bits of each byte from the least significant one (legacy lists)")]
+    lsb0: bool,
+
+    #[command(flatten)]
+    ledger: LedgerArgs,
+  },
+}
 
-  //Creazione nuova revocation list
-  let mut new_rl = RevocationList2020::RevocationList2020::new("https://example.com/credentials/status/3", 16).unwrap();
 
-  //Revoca delle credenziali
-  for c in cred_arr
+
+#[tokio::main]
+async fn main() -> ExitCode
+{
+  let cli = Cli::parse();
+  let result = match cli.command
   {
-    let r_index : String = c.clone().credential_status.unwrap().properties.get("revocationListIndex").unwrap().to_string();
-    new_rl.revoke(r_index.replace("\"","").parse::<u64>().unwrap());
+    Command::List(command) => list(command).await.map(|_| ExitCode::SUCCESS),
+    Command::Credential(command) => credential(command).await,
+  };
+
+  match result
+  {
+    Ok(code) => code,
+    Err(e) =>
+    {
+      eprintln!("error: {}", e);
+      ExitCode::from(EXIT_ERROR)
+    }
   }
+}
+
 
-  //////////////////////////////////////////////////////Creazione nuova VC lista
-  
-  let mut new_list_coded : String = new_rl.get_encList();
-
-  //Ricreo soggetto
-  let new_sub: Subject = Subject::from_json_value(json!({
-    "id": issuer.document().id(),
-    "encodedList": new_list_coded,
-  }))?;
-
-
-  //Creazione nuova VC lista
-  let mut new_lista_VC: Credential = CredentialBuilder::default()
-  .id(Url::parse("https://example.com/credentials/status/3")?)
-  .context(Url::parse("https://w3id.org/vc-revocation-list-2020/v1").unwrap())
-  .issuer(Url::parse(issuer.did().as_str())?)
-  .type_("RevocationList2020Credential")
-  .subject(new_sub)
-  .build()?;
-
-  //Firma
-  issuer
-  .sign("#key-1", &mut new_lista_VC, ProofOptions::default())
-  .await?;
-
-
-  ///////////////////////////////////Invio della nuova RevocationList sulla Tangle
-
-  //VC -> payload
-  let s_list = String::from(new_lista_VC.to_string());
-  let mut new_credential : Vec<u8> = s_list.into_bytes();
-
-
-  //Invio sulla Tangle
-  let new_sent = iota_cl
-    .message()
-    .with_index(index_VC_list)
-    .with_data(new_credential)
-    .finish()
-    .await;
-  
-
-  ////////////////// Esempio d'uso RevocationList: presuppongo che le credenziali revocabili siano in possesso dei rispettivi holder
-  
-  //1) Un client (Alice) condivide la sua VC con un server, il quale verifica se questa è ancora valida o è stata revocata, prima di connettersi
-
-  //Avendo la credenziale, recupero lo IotaDocument dell'issuer (partendo dal suo DID)
-  let issuer_did = credential_A.issuer.url().clone().into_string();
-  let iota = Client::builder().build().await?;
-  let resolved_doc = iota.read_document(&IotaDID::parse(issuer_did.clone())?).await.unwrap();
-  let issuer_doc = resolved_doc.document;
-
-  //Verifico la firma dell'issuer sulla credenziale
-  CredentialValidator::verify_signature(
-    &credential_A,
-    std::slice::from_ref(issuer_doc.as_ref()),
-    &CredentialValidationOptions::default().verifier_options,
-    )
-    .unwrap();
-  
-  //Recupero l'indice della VC, l'id della sua RevocationList e l'indice del messaggio contenente la RevocationList
-  let index : String = credential_A.clone().credential_status.unwrap().properties.get("revocationListIndex").unwrap().to_string().replace("\"","");
-  let id_list : String = credential_A.clone().credential_status.unwrap().properties.get("revocationListCredential").unwrap().to_string().replace("\"","");
-  let indice_lista : String = credential_A.clone().properties.get("IndiceRL").unwrap().to_string().replace("\"","");
-
-  //////////////////////////////////////// Recuperare la lista con la issuance date più recente
-
-  //Ottengo vettore di MessageId con l'index passato
-  let elenco_messaggi = iota_cl.get_message().index(index_VC_list).await.unwrap();
-  let vettore_messaggi = elenco_messaggi.into_vec();
-
-  let mut newest : Timestamp = Timestamp::parse("1970-01-01T00:00:00Z").unwrap();
-  let mut latest_listID : MessageId = MessageId::null();
-
-  //Ciclo allo scopo di trovare il MessageId corrispondente alla versione più recente della lista
-  for msg_id in vettore_messaggi
+async fn list(command: ListCommand) -> CliResult<()>
+{
+  match command
   {
-    let messaggio = iota_cl.get_message().data(&msg_id).await.unwrap();
-    let payload = messaggio.payload().clone().unwrap().pack_new();
-    let payload_stringa = String::from_utf8_lossy(&payload).to_string();
-    let mut list_array : Vec<&str> = payload_stringa.split("{").collect();
-    list_array.remove(0);
-    let mut i = 0;
-    let mut result = "{".to_string();
-    while i < list_array.len()
+    ListCommand::Create { id, size, lsb0, file } =>
     {
-      result+= list_array[i];
-      result+= "{";
-      i+=1;
+      let list = RevocationList2020::new(&id, size)?.with_bit_order(bit_order(lsb0));
+      write_list(file.list.as_deref(), &ListRecord::new(list), true)
     }
-    //Elimino ultima {
-    result.pop();
 
-    //Ottengo la Credential
-    let mut credential_list : Credential = serde_json::from_str::<Credential>(&result).unwrap();
+    ListCommand::Revoke { file, indices } =>
+    {
+      let mut record = read_list(file.list.as_deref())?;
+      let changed = record.list.revoke_many(indices)?;
+      write_list(file.list.as_deref(), &record, false)?;
+      eprintln!("revoked: {:?}", changed);
+      Ok(())
+    }
 
-    //Recupero la issuance date e verifico se è più recente
-    let mut issuance_date : Timestamp = credential_list.issuance_date;
-    if issuance_date.gt(&newest)
+    ListCommand::Reset { file, indices } =>
     {
-      newest = issuance_date;
-      latest_listID = msg_id.clone();
+      let mut record = read_list(file.list.as_deref())?;
+      let changed = record.list.reset_many(indices)?;
+      write_list(file.list.as_deref(), &record, false)?;
+      eprintln!("reset: {:?}", changed);
+      Ok(())
     }
-  }
 
-  //Il messaggio corretto
-  let mex = iota_cl.get_message().data(&latest_listID).await.unwrap();
+    ListCommand::Status { file, indices } =>
+    {
+      let record = read_list(file.list.as_deref())?;
+      for index in indices
+      {
+        println!("{} {}", index, if record.list.is_revoked(index)? { "revoked" } else { "valid" });
+      }
+      Ok(())
+    }
+
+    ListCommand::Encode { file, issuer } =>
+    {
+      let mut record = read_list(file.list.as_deref())?;
+      match issuer
+      {
+        Some(issuer) => println!("{}", record.list_credential(&Url::parse(issuer)?)?.to_json_pretty()?),
+        None => println!("{}", record.list.encode()?),
+      }
+      Ok(())
+    }
+
+    ListCommand::Decode { id, lsb0, encoded, file } =>
+    {
+      let encoded = match encoded
+      {
+        Some(encoded) => encoded,
+        None => String::from_utf8(read_input(None)?)?,
+      };
+      let list = RevocationList2020::new_withList(&id, encoded.trim())?.with_bit_order(bit_order(lsb0));
+
+      //Gli indici già revocati sono stati assegnati a qualche credenziale e non vanno riassegnati
+      let mut record = ListRecord::new(list);
+      for index in record.list.iter_revoked().collect::<Vec<u64>>()
+      {
+        record.allocator.allocate_index(index)?;
+      }
+      write_list(file.list.as_deref(), &record, true)
+    }
 
-  /////////////////////////// Ora posso recuperare la lista corretta
+    ListCommand::Inspect { input, lsb0, json } =>
+    {
+      let report = inspect(&String::from_utf8(read_input(input.as_deref())?)?, bit_order(lsb0))?;
+      if json
+      {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+      }
+      else
+      {
+        println!("{}", report);
+      }
+      Ok(())
+    }
 
-  //Metodo lossy a causa dei caratteri speciali nella parte del MessageId
-  let pl = mex.payload().clone().unwrap().pack_new();
-  let pl_stringa = String::from_utf8_lossy(&pl).to_string();
+    ListCommand::Publish { file, account, ledger, index } =>
+    {
+      let mut record = read_list(file.list.as_deref())?;
+      let issuer = load_account(&account).await?;
 
-  //Conversione in Credential della credenziale lista
-  let mut list_vec : Vec<&str> = pl_stringa.split("{").collect();
-  list_vec.remove(0);
-  let mut j = 0;
-  let mut risultato = "{".to_string();
-  while j < list_vec.len()
+      let mut credential = record.list_credential(&Url::parse(issuer.did().as_str())?)?;
+      AccountSigner { account: &issuer, fragment: &account.fragment }.sign(&mut credential).await?;
+
+      let index = index.unwrap_or_else(|| record.list_id().to_owned());
+      let payload = encode_credential(&credential, Compression::Gzip)?;
+      let publication = open_ledger(&ledger).await?.publish(&index, &payload).await?;
+
+      record.set_published(&credential, &publication);
+      write_list(file.list.as_deref(), &record, false)?;
+      eprintln!("published {} under index {}", publication, index);
+      Ok(())
+    }
+  }
+}
+
+
+async fn credential(command: CredentialCommand) -> CliResult<ExitCode>
+{
+  match command
   {
-    risultato+= list_vec[j];
-    risultato+= "{";
-    j+=1;
+    CredentialCommand::Check { credential, issuer_document, lsb0, ledger } =>
+    {
+      let credential = Credential::from_json_slice(&read_input(credential.as_deref())?)?;
+      let source = open_ledger(&ledger).await?;
+
+      let verdict = match issuer_document
+      {
+        Some(path) =>
+        {
+          let document = IotaDocument::from_json_slice(&read_input(Some(&path))?)?;
+          check_status(&credential, &document, source.as_ref(), bit_order(lsb0)).await
+        }
+        None => check_status(&credential, &Resolver::new().await?, source.as_ref(), bit_order(lsb0)).await,
+      };
+
+      match verdict
+      {
+        StatusVerdict::Valid { list } =>
+        {
+          println!("valid (list {} issued {})", list.list_id, list.issuance_date);
+          Ok(ExitCode::SUCCESS)
+        }
+        StatusVerdict::Revoked { list } =>
+        {
+          println!("revoked (list {} issued {})", list.list_id, list.issuance_date);
+          Ok(ExitCode::from(EXIT_REVOKED))
+        }
+        StatusVerdict::Unknown { reason } =>
+        {
+          println!("unknown ({})", reason);
+          Ok(ExitCode::from(EXIT_ERROR))
+        }
+      }
+    }
   }
-    
-  risultato.pop();
-    
-  let mut credential_lista : Credential = serde_json::from_str::<Credential>(&risultato).unwrap();
+}
 
 
 
-  ///////////////////////////////////////////////////////////Verifiche su quanto ricostruito
+//Ledger su cui pubblicare e da cui leggere le liste
+trait Ledger: StatusListPublisher + StatusListSource {}
 
-  //Verifica della firma sulla lista credenziale
-  let id_client = Client::builder().build().await?;
-  let mut issuer_document = id_client.read_document(&IotaDID::parse(issuer_did)?).await.unwrap().document;
-  issuer_document.verify_data(&credential_lista, &CredentialValidationOptions::default().verifier_options).unwrap();
+impl<L: StatusListPublisher + StatusListSource> Ledger for L {}
+
+async fn open_ledger(args: &LedgerArgs) -> CliResult<Box<dyn Ledger>>
+{
+  match (&args.ledger, &args.node)
+  {
+    (Some(dir), _) => Ok(Box::new(FileLedger::new(dir))),
+    (None, Some(node)) => Ok(Box::new(IotaIndexation::with_node(node).await?)),
+    (None, None) => Err("either --node or --ledger is required".into()),
+  }
+}
+
+
+async fn load_account(args: &AccountArgs) -> CliResult<Account>
+{
+  let stronghold = Stronghold::new(&args.stronghold, args.password.clone(), None).await?;
+  let account = Account::builder()
+    .storage(stronghold)
+    .load_identity(IotaDID::parse(&args.did)?)
+    .await?;
+  Ok(account)
+}
 
-  //Recupero id della credenziale lista e lo confronto con quello della mia credenziale
-  let lista_id = credential_lista.id.unwrap().into_string();
-  if lista_id.ne(&id_list.clone().replace("\"","")) {
-        panic!("ERRORE: ID liste differenti");
-      }
 
-  ///////////////////////////////////////////////////////////Infine costruisco oggetto RevocationList2020 e check revoca
+fn bit_order(lsb0: bool) -> BitOrder
+{
+  if lsb0 { BitOrder::Lsb0 } else { BitOrder::Msb0 }
+}
 
-  //Recupero della encodedList
-  let sub = credential_lista.credential_subject.into_vec();
-  let eL = sub[0].properties.get("encodedList").unwrap().to_string().replace("\"","");
 
-  //Costruisco la RevocationList con la encodedList recuperata
-  let mut rl = RevocationList2020::RevocationList2020::new_withList(&lista_id, &eL).unwrap();
 
-  //Check se credenziale revocata
-  if rl.is_revoked(index.parse::<u64>().unwrap()).unwrap() {
-    println!("La credenziale è stata revocata, connessione negata!");
+fn read_input(path: Option<&Path>) -> CliResult<Vec<u8>>
+{
+  match path
+  {
+    Some(path) => fs::read(path).map_err(|e| format!("reading {}: {}", path.display(), e).into()),
+    None =>
+    {
+      let mut bytes = Vec::new();
+      std::io::stdin().read_to_end(&mut bytes)?;
+      Ok(bytes)
+    }
   }
-  else {
-    println!("Credenziale valida, connessione accettata");
+}
+
+fn read_list(path: Option<&Path>) -> CliResult<ListRecord>
+{
+  Ok(decode_record(&read_input(path)?)?)
+}
+
+//Riscrive la lista sul suo file, o su stdout. Con `create` un file esistente non viene sovrascritto,
+//così una lista con delle revoche non viene mai rimpiazzata da una vuota.
+fn write_list(path: Option<&Path>, record: &ListRecord, create: bool) -> CliResult<()>
+{
+  let bytes = encode_record(record)?;
+  match path
+  {
+    Some(path) => write_atomic(path, &bytes, create).map_err(|e| format!("writing {}: {}", path.display(), e).into()),
+    None =>
+    {
+      let mut stdout = std::io::stdout();
+      stdout.write_all(&bytes)?;
+      writeln!(stdout)?;
+      Ok(())
+    }
   }
+}
+
+//Scrive su un file temporaneo accanto a `path` e lo sposta al suo posto, così il file non resta mai a metà
+fn write_atomic(path: &Path, bytes: &[u8], create: bool) -> std::io::Result<()>
+{
+  let mut tmp = OsString::from(path.as_os_str());
+  tmp.push(format!(".{}.tmp", std::process::id()));
+  let tmp = PathBuf::from(tmp);
 
+  let written = fs::File::create(&tmp)
+    .and_then(|mut file| file.write_all(bytes).and_then(|_| file.sync_all()))
+    .and_then(|_| if create { fs::hard_link(&tmp, path) } else { fs::rename(&tmp, path) });
 
-  Ok(())
-}
\ No newline at end of file
+  let _ = fs::remove_file(&tmp);
+  written
+}
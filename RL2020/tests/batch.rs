@@ -0,0 +1,51 @@
+#![allow(non_snake_case)]
+
+use ::RevocationList2020::{CredentialError, RevocationList2020, RevocationStatus};
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+#[test]
+fn revoke_many_reports_changed_indices()
+{
+    let mut rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+    rl.revoke(100).unwrap();
+    rl.encode().unwrap();
+
+    let changed = rl.revoke_many(vec![2500, 7, 100, 7]).unwrap();
+    assert_eq!(changed, vec![7, 2500]);
+    assert!(rl.is_revoked(7).unwrap() && rl.is_revoked(100).unwrap() && rl.is_revoked(2500).unwrap());
+
+    assert_eq!(rl.reset_many(0..10).unwrap(), vec![7]);
+}
+
+#[test]
+fn out_of_range_batch_leaves_list_untouched()
+{
+    let mut rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+    let before = rl.get_encList().unwrap();
+
+    let result = rl.revoke_many(vec![1, 2, 131072, 3]);
+    assert!(matches!(result, Err(CredentialError::IndexOutOfRange { index: 131072, .. })));
+    assert!(!rl.is_dirty());
+    assert_eq!(rl.get_encList().unwrap(), before);
+    assert!(!rl.is_revoked(1).unwrap());
+}
+
+#[test]
+fn apply_reports_net_changes()
+{
+    let mut rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+
+    let changed = rl
+        .apply(vec![
+            (5, RevocationStatus::Revoke),
+            (5, RevocationStatus::Reset),
+            (6, RevocationStatus::Revoke),
+            (9, RevocationStatus::Reset),
+        ])
+        .unwrap();
+
+    assert_eq!(changed, vec![6]);
+    assert_eq!(rl.get(5).unwrap(), RevocationStatus::Reset);
+    assert_eq!(rl.get(6).unwrap(), RevocationStatus::Revoke);
+}
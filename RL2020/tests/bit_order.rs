@@ -0,0 +1,135 @@
+#![allow(non_snake_case)]
+
+use std::io::Read;
+use std::io::Write;
+
+use base64::{decode_config, encode_config, STANDARD};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use ::RevocationList2020::BitstringStatusList::{BitstringStatusList, BitstringStatusPurpose};
+use ::RevocationList2020::IssuerStore::{decode_record, encode_record, ListRecord};
+use ::RevocationList2020::StatusList2021::{StatusList2021, StatusPurpose};
+use ::RevocationList2020::{BitOrder, RevocationList2020};
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+//Esempio di encodedList pubblicato nelle specifiche StatusList2021 e Bitstring Status List (con prefisso 'u'):
+//16 kb GZIP, nessun bit impostato
+const SPEC_EMPTY_LIST: &str = "H4sIAAAAAAAAA-3BMQEAAADCoPVPbQwfoAAAAAAAAAAAAAAAAAAAAIC3AYbSVKsAQAAA";
+
+//Liste di 16 kb con il solo indice 94567 (lo statusListIndex degli esempi W3C) impostato, prodotte fuori da questo
+//crate: in Python il testo di 131072 caratteri '0'/'1' con '1' in posizione 94567 convertito con
+//int(text, 2).to_bytes(16384, 'big'), poi zlib.compress + base64 e gzip.compress(mtime=0) + base64url senza padding
+const VECTOR_INDEX: u64 = 94567;
+const VECTOR_ZLIB_BASE64: &str = "eNrt0AENAAAMAiDtX9oc/yACCQAAAAAAAAAAAAAA31QBAAAAwDkDUdQAAg==";
+const VECTOR_GZIP_BASE64URL: &str = "H4sIAAAAAAACA-3QAQ0AAAwCIO1f2hz_IAIJAAAAAAAAAAAAAADfVAEAAADAOQNQdb5gAEAAAA";
+
+fn encode(bits: &[u8]) -> String
+{
+    let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
+    e.write_all(bits).unwrap();
+    encode_config(e.finish().unwrap(), STANDARD)
+}
+
+fn decode(encoded: &str) -> Vec<u8>
+{
+    let bin = decode_config(encoded, STANDARD).unwrap();
+    let mut buf = Vec::new();
+    ZlibDecoder::new(&*bin).read_to_end(&mut buf).unwrap();
+    buf
+}
+
+#[test]
+fn spec_example_decodes_to_an_empty_list()
+{
+    let list = StatusList2021::new_withList(LIST_ID, StatusPurpose::Revocation, SPEC_EMPTY_LIST).unwrap();
+    assert_eq!(list.capacity(), 131072);
+    assert!((0..131072).all(|i| !list.get(i).unwrap()));
+
+    let multibase = format!("u{}", SPEC_EMPTY_LIST);
+    let list = BitstringStatusList::new_withList(LIST_ID, BitstringStatusPurpose::Revocation, 1, &multibase).unwrap();
+    assert_eq!(list.capacity(), 131072);
+}
+
+#[test]
+fn external_vectors_report_their_index()
+{
+    let rl = RevocationList2020::new_withList(LIST_ID, VECTOR_ZLIB_BASE64).unwrap();
+    assert_eq!(rl.iter_revoked().collect::<Vec<u64>>(), vec![VECTOR_INDEX]);
+
+    let sl = StatusList2021::new_withList(LIST_ID, StatusPurpose::Revocation, VECTOR_GZIP_BASE64URL).unwrap();
+    assert!(sl.get(VECTOR_INDEX).unwrap());
+    assert_eq!((0..sl.capacity() as u64).filter(|i| sl.get(*i).unwrap()).count(), 1);
+
+    //Letta con l'ordine precedente la stessa lista indica un'altra credenziale
+    let legacy = RevocationList2020::new_withList(LIST_ID, VECTOR_ZLIB_BASE64).unwrap().with_bit_order(BitOrder::Lsb0);
+    assert_eq!(legacy.iter_revoked().collect::<Vec<u64>>(), vec![VECTOR_INDEX - 7]);
+}
+
+#[test]
+fn revoke_matches_external_vectors()
+{
+    //Il compressore è diverso, quindi si confrontano i byte decompressi
+    let mut rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+    rl.revoke(VECTOR_INDEX).unwrap();
+    assert_eq!(decode(&rl.get_encList().unwrap()), decode(VECTOR_ZLIB_BASE64));
+
+    let mut sl = StatusList2021::new(LIST_ID, StatusPurpose::Revocation, 16).unwrap();
+    sl.set(VECTOR_INDEX, true).unwrap();
+    let ours = StatusList2021::new_withList(LIST_ID, StatusPurpose::Revocation, &sl.get_encList().unwrap()).unwrap();
+    let theirs = StatusList2021::new_withList(LIST_ID, StatusPurpose::Revocation, VECTOR_GZIP_BASE64URL).unwrap();
+    assert!((0..131072).all(|i| ours.get(i).unwrap() == theirs.get(i).unwrap()));
+}
+
+#[test]
+fn legacy_order_reads_published_lists()
+{
+    //Lista pubblicata con l'ordine precedente: indice 7 e 2500 revocati
+    let mut bits = vec![0u8; 16 * 1024];
+    bits[0] = 0b1000_0000;
+    bits[312] = 0b0001_0000;
+    let el = encode(&bits);
+
+    let legacy = RevocationList2020::new_withList(LIST_ID, &el).unwrap().with_bit_order(BitOrder::Lsb0);
+    assert_eq!(legacy.bit_order(), BitOrder::Lsb0);
+    assert!(legacy.is_revoked(7).unwrap());
+    assert!(legacy.is_revoked(2500).unwrap());
+    assert!(!legacy.is_revoked(0).unwrap());
+
+    let spec = RevocationList2020::new_withList(LIST_ID, &el).unwrap();
+    assert_eq!(spec.bit_order(), BitOrder::Msb0);
+    assert!(spec.is_revoked(0).unwrap());
+    assert!(!spec.is_revoked(7).unwrap());
+}
+
+#[test]
+fn reset_clears_only_target_bit()
+{
+    let mut rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+    rl.revoke(8).unwrap();
+    rl.revoke(9).unwrap();
+    rl.reset(8).unwrap();
+
+    assert_eq!(decode(&rl.get_encList().unwrap())[1], 0b0100_0000);
+}
+
+#[test]
+fn legacy_order_survives_persistence()
+{
+    let mut legacy = RevocationList2020::new(LIST_ID, 16).unwrap().with_bit_order(BitOrder::Lsb0);
+    legacy.revoke(0).unwrap();
+
+    //Con l'ordine sbagliato l'indice 0 ricaricato comparirebbe come indice 7
+    let check = |loaded: RevocationList2020|
+    {
+        assert_eq!(loaded.bit_order(), BitOrder::Lsb0);
+        assert!(loaded.is_revoked(0).unwrap());
+        assert!(!loaded.is_revoked(7).unwrap());
+    };
+
+    check(serde_json::from_str(&serde_json::to_string(&legacy).unwrap()).unwrap());
+    check(RevocationList2020::from_snapshot(&legacy.to_snapshot().unwrap()).unwrap());
+    check(decode_record(&encode_record(&ListRecord::new(legacy.clone())).unwrap()).unwrap().list);
+}
@@ -0,0 +1,96 @@
+#![allow(non_snake_case)]
+
+use identity_core::common::Url;
+use identity_credential::credential::Status;
+use identity_did::did::CoreDID;
+use identity_did::did::DIDUrl;
+
+use ::RevocationList2020::Bitstring::{Bitstring, Codec};
+use ::RevocationList2020::BitstringStatusList::{BitstringStatusList, BitstringStatusPurpose, StatusMessage, MIN_STATUS_LIST_ENTRIES};
+use ::RevocationList2020::BitstringStatusListEntry::BitstringStatusListEntry;
+use ::RevocationList2020::CredentialError;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+fn messages() -> Vec<StatusMessage>
+{
+    vec![
+        StatusMessage::new(0x0, "pending_review"),
+        StatusMessage::new(0x1, "accepted"),
+        StatusMessage::new(0x2, "rejected"),
+        StatusMessage::new(0x3, "undefined"),
+    ]
+}
+
+#[test]
+fn multi_bit_entries_round_trip()
+{
+    let mut list = BitstringStatusList::new(LIST_ID, BitstringStatusPurpose::Message, MIN_STATUS_LIST_ENTRIES, 2)
+        .unwrap()
+        .with_messages(messages())
+        .unwrap();
+    assert_eq!(list.capacity(), MIN_STATUS_LIST_ENTRIES);
+
+    list.set(0, 0x2).unwrap();
+    list.set(94567, 0x1).unwrap();
+    assert!(matches!(list.set(1, 0x4), Err(CredentialError::StatusValueOutOfRange { value: 4, status_size: 2 })));
+
+    let el = list.encode().unwrap().to_string();
+    assert!(el.starts_with("uH4sI"));
+
+    let decoded = BitstringStatusList::new_withList(LIST_ID, BitstringStatusPurpose::Message, 2, &el)
+        .unwrap()
+        .with_messages(messages())
+        .unwrap();
+    assert_eq!(decoded.get(0).unwrap(), 0x2);
+    assert_eq!(decoded.get(94567).unwrap(), 0x1);
+    assert_eq!(decoded.message(94567).unwrap(), Some("accepted"));
+    assert_eq!(decoded.message(1).unwrap(), Some("pending_review"));
+}
+
+#[test]
+fn entries_are_msb_first()
+{
+    let mut list = BitstringStatusList::new(LIST_ID, BitstringStatusPurpose::Message, MIN_STATUS_LIST_ENTRIES, 2).unwrap();
+    list.set(0, 0x2).unwrap();
+
+    //0x2 nella prima entry corrisponde ai bit "10" più a sinistra
+    let el = list.get_encList().unwrap();
+    let bits = Bitstring::unpack(&el, Codec::GzipMultibase).unwrap();
+    assert_eq!(bits.as_bytes()[0], 0b1000_0000);
+}
+
+#[test]
+fn enforces_minimum_length_and_message_table()
+{
+    assert!(matches!(
+        BitstringStatusList::new(LIST_ID, BitstringStatusPurpose::Revocation, 1000, 1),
+        Err(CredentialError::TooFewEntries { entries: 1000, .. })
+    ));
+
+    let list = BitstringStatusList::new(LIST_ID, BitstringStatusPurpose::Message, MIN_STATUS_LIST_ENTRIES, 2).unwrap();
+    assert!(list.with_messages(messages()[..2].to_vec()).is_err());
+
+    //Una lista da 131072 entry da 2 bit non può essere letta come lista da 4 bit
+    let mut list = BitstringStatusList::new(LIST_ID, BitstringStatusPurpose::Message, MIN_STATUS_LIST_ENTRIES, 2).unwrap();
+    let el = list.encode().unwrap().to_string();
+    assert!(BitstringStatusList::new_withList(LIST_ID, BitstringStatusPurpose::Message, 4, &el).is_err());
+    assert!(BitstringStatusList::new_withList(LIST_ID, BitstringStatusPurpose::Message, 2, &el[1..]).is_err());
+}
+
+#[test]
+fn entry_round_trips_through_status()
+{
+    let did = CoreDID::parse("did:example:issuer").unwrap();
+    let entry = BitstringStatusListEntry::new(DIDUrl::new(did.clone(), None), BitstringStatusPurpose::Message, 94567, Url::parse(LIST_ID).unwrap(), 2, &messages()).unwrap();
+
+    let status: Status = entry.into();
+    let parsed = BitstringStatusListEntry::try_from(status).unwrap();
+    assert_eq!(parsed.index().unwrap(), 94567);
+    assert_eq!(parsed.purpose().unwrap(), BitstringStatusPurpose::Message);
+    assert_eq!(parsed.list_credential().as_str(), LIST_ID);
+    assert_eq!(parsed.status_size().unwrap(), 2);
+    assert_eq!(parsed.messages().unwrap(), messages());
+
+    assert!(BitstringStatusListEntry::new(DIDUrl::new(did, None), BitstringStatusPurpose::Message, 1, Url::parse(LIST_ID).unwrap(), 2, &[]).is_err());
+}
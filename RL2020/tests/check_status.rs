@@ -0,0 +1,91 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use identity_iota::core::Timestamp;
+use identity_iota::core::Value;
+
+use ::RevocationList2020::BitOrder;
+use ::RevocationList2020::CredentialError;
+use ::RevocationList2020::Ledger::{MemoryLedger, StatusListPublisher};
+use ::RevocationList2020::Verifier::{check_status, StatusVerdict, LIST_INDEX_PROPERTY};
+
+use common::Issuer;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+#[tokio::test]
+async fn reports_valid_and_revoked_with_the_list_version()
+{
+    let issuer = Issuer::new();
+    let ledger = MemoryLedger::new();
+    ledger.publish(LIST_ID, &issuer.list(LIST_ID, &[7], "2022-01-01T00:00:00Z")).await.unwrap();
+    ledger.publish(LIST_ID, &issuer.list(LIST_ID, &[7, 8], "2022-02-01T00:00:00Z")).await.unwrap();
+
+    let expected = Timestamp::parse("2022-02-01T00:00:00Z").unwrap();
+    for (index, revoked) in [(7, true), (8, true), (9, false)]
+    {
+        match check_status(&issuer.credential(index, LIST_ID), &issuer.document, &ledger, BitOrder::Msb0).await
+        {
+            StatusVerdict::Revoked { list } if revoked => assert_eq!(list.issuance_date, expected),
+            StatusVerdict::Valid { list } if !revoked => assert_eq!(list.list_id.as_str(), LIST_ID),
+            other => panic!("unexpected verdict {:?} for index {}", other, index),
+        }
+    }
+}
+
+#[tokio::test]
+async fn uses_the_ledger_index_of_the_credential()
+{
+    let issuer = Issuer::new();
+    let ledger = MemoryLedger::new();
+    ledger.publish("RL2020_MyList", &issuer.list(LIST_ID, &[7], "2022-01-01T00:00:00Z")).await.unwrap();
+
+    let mut credential = issuer.credential(7, LIST_ID);
+    credential.properties.insert(LIST_INDEX_PROPERTY.to_owned(), Value::String("RL2020_MyList".to_owned()));
+    issuer.sign(&mut credential);
+
+    assert!(check_status(&credential, &issuer.document, &ledger, BitOrder::Msb0).await.is_revoked());
+}
+
+#[tokio::test]
+async fn unknown_when_the_status_cannot_be_determined()
+{
+    let issuer = Issuer::new();
+    let other = Issuer::new();
+    let ledger = MemoryLedger::new();
+
+    //Nessuna lista pubblicata
+    let verdict = check_status(&issuer.credential(7, LIST_ID), &issuer.document, &ledger, BitOrder::Msb0).await;
+    assert!(matches!(verdict, StatusVerdict::Unknown { reason: CredentialError::ListNotFound { .. } }));
+
+    //Issuer non risolvibile
+    ledger.publish(LIST_ID, &issuer.list(LIST_ID, &[], "2022-01-01T00:00:00Z")).await.unwrap();
+    let verdict = check_status(&issuer.credential(7, LIST_ID), &other.document, &ledger, BitOrder::Msb0).await;
+    assert!(matches!(verdict, StatusVerdict::Unknown { reason: CredentialError::IssuerResolution(_) }));
+
+    //Indice oltre la capacità della lista
+    let verdict = check_status(&issuer.credential(1 << 20, LIST_ID), &issuer.document, &ledger, BitOrder::Msb0).await;
+    assert!(matches!(verdict, StatusVerdict::Unknown { reason: CredentialError::IndexOutOfRange { .. } }));
+
+    //Credenziale senza status
+    let mut credential = issuer.credential(7, LIST_ID);
+    credential.credential_status = None;
+    let verdict = check_status(&credential, &issuer.document, &ledger, BitOrder::Msb0).await;
+    assert!(matches!(verdict, StatusVerdict::Unknown { reason: CredentialError::MissingStatus }));
+}
+
+#[tokio::test]
+async fn reads_legacy_lists_with_their_bit_order()
+{
+    let issuer = Issuer::new();
+    let ledger = MemoryLedger::new();
+    ledger.publish(LIST_ID, &issuer.legacy_list(LIST_ID, &[7], "2022-01-01T00:00:00Z")).await.unwrap();
+
+    assert!(check_status(&issuer.credential(7, LIST_ID), &issuer.document, &ledger, BitOrder::Lsb0).await.is_revoked());
+    assert!(!check_status(&issuer.credential(0, LIST_ID), &issuer.document, &ledger, BitOrder::Lsb0).await.is_revoked());
+
+    //Letta come lista conforme alla specifica la revoca cade sull'indice sbagliato
+    assert!(check_status(&issuer.credential(0, LIST_ID), &issuer.document, &ledger, BitOrder::Msb0).await.is_revoked());
+    assert!(!check_status(&issuer.credential(7, LIST_ID), &issuer.document, &ledger, BitOrder::Msb0).await.is_revoked());
+}
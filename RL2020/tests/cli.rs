@@ -0,0 +1,144 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Output;
+use std::process::Stdio;
+
+use identity_iota::core::ToJson;
+
+use ::RevocationList2020::Ledger::{FileLedger, StatusListPublisher};
+
+use common::Issuer;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+fn temp_dir(name: &str) -> PathBuf
+{
+    let dir = std::env::temp_dir().join(format!("rl2020-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+//Esegue il binario, passando `stdin` se presente
+fn rl2020(args: &[&str], stdin: Option<&[u8]>) -> Output
+{
+    let mut child = Command::new(env!("CARGO_BIN_EXE_RL2020"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.unwrap_or_default()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String
+{
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn manages_a_list_file()
+{
+    let dir = temp_dir("cli-file");
+    let list = dir.join("list.json");
+    let list = list.to_str().unwrap();
+
+    stdout(&rl2020(&["list", "create", "--id", LIST_ID, "--list", list], None));
+    stdout(&rl2020(&["list", "revoke", "--list", list, "3", "5"], None));
+    stdout(&rl2020(&["list", "reset", "--list", list, "5"], None));
+    assert_eq!(stdout(&rl2020(&["list", "status", "--list", list, "3", "5"], None)), "3 revoked\n5 valid\n");
+
+    //Una lista esistente non viene sovrascritta da una vuota
+    assert_eq!(rl2020(&["list", "create", "--id", LIST_ID, "--list", list], None).status.code(), Some(2));
+    assert_eq!(stdout(&rl2020(&["list", "status", "--list", list, "3"], None)), "3 revoked\n");
+
+    //Indici fuori dalla lista: nessuna modifica
+    assert_eq!(rl2020(&["list", "revoke", "--list", list, "4", "999999999"], None).status.code(), Some(2));
+    assert_eq!(stdout(&rl2020(&["list", "status", "--list", list, "4"], None)), "4 valid\n");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn pipes_lists_through_stdin_and_stdout()
+{
+    let created = stdout(&rl2020(&["list", "create", "--id", LIST_ID], None));
+    let revoked = stdout(&rl2020(&["list", "revoke", "7"], Some(created.as_bytes())));
+
+    let encoded = stdout(&rl2020(&["list", "encode"], Some(revoked.as_bytes())));
+    let decoded = stdout(&rl2020(&["list", "decode", "--id", LIST_ID], Some(encoded.as_bytes())));
+    assert_eq!(stdout(&rl2020(&["list", "status", "7", "8"], Some(decoded.as_bytes()))), "7 revoked\n8 valid\n");
+
+    let credential = stdout(&rl2020(&["list", "encode", "--issuer", "did:example:issuer"], Some(revoked.as_bytes())));
+    assert!(credential.contains("RevocationList2020Credential") && credential.contains(encoded.trim()));
+}
+
+#[test]
+fn inspects_encoded_lists()
+{
+    let created = stdout(&rl2020(&["list", "create", "--id", LIST_ID], None));
+    let revoked = stdout(&rl2020(&["list", "revoke", "7", "8", "20"], Some(created.as_bytes())));
+    let encoded = stdout(&rl2020(&["list", "encode"], Some(revoked.as_bytes())));
+
+    let report = stdout(&rl2020(&["list", "inspect"], Some(encoded.as_bytes())));
+    assert!(report.contains("revoked:     3") && report.contains("ranges:      7-8, 20"));
+
+    let credential = stdout(&rl2020(&["list", "encode", "--issuer", "did:example:issuer"], Some(revoked.as_bytes())));
+    let json: serde_json::Value = serde_json::from_str(&stdout(&rl2020(&["list", "inspect", "--json"], Some(credential.as_bytes())))).unwrap();
+    assert_eq!(json["id"], LIST_ID);
+    assert_eq!(json["ranges"], serde_json::json!([[7, 8], [20, 20]]));
+}
+
+#[tokio::test]
+async fn checks_credentials_against_a_ledger_directory()
+{
+    let dir = temp_dir("cli-check");
+    let issuer = Issuer::new();
+    let ledger = FileLedger::new(dir.join("ledger"));
+    ledger.publish(LIST_ID, &issuer.list(LIST_ID, &[7], "2022-01-01T00:00:00Z")).await.unwrap();
+
+    let document = dir.join("issuer.json");
+    std::fs::write(&document, issuer.document.to_json().unwrap()).unwrap();
+
+    let check = |index: u64|
+    {
+        let mut credential = issuer.credential(index, LIST_ID);
+        issuer.sign(&mut credential);
+        rl2020(
+            &["credential", "check", "--ledger", dir.join("ledger").to_str().unwrap(), "--issuer-document", document.to_str().unwrap()],
+            Some(credential.to_json().unwrap().as_bytes()),
+        )
+    };
+
+    let revoked = check(7);
+    assert_eq!(revoked.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&revoked.stdout).starts_with("revoked"));
+    assert_eq!(check(8).status.code(), Some(0));
+
+    //Nessuna lista pubblicata nella directory
+    std::fs::remove_dir_all(dir.join("ledger")).unwrap();
+    assert_eq!(check(7).status.code(), Some(2));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn requires_a_node_or_a_ledger_directory()
+{
+    //Senza un nodo predefinito il comando non parte, né con entrambe le opzioni
+    let missing = rl2020(&["credential", "check"], Some(b"{}"));
+    assert_eq!(missing.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("--node"));
+
+    let both = rl2020(&["credential", "check", "--node", "https://node.example", "--ledger", "ledger"], Some(b"{}"));
+    assert_eq!(both.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&both.stderr).contains("cannot be used with"));
+}
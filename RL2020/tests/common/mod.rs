@@ -0,0 +1,89 @@
+#![allow(dead_code)]
+
+use identity_iota::core::Timestamp;
+use identity_iota::core::Url;
+use identity_iota::core::FromJson;
+use identity_iota::core::json;
+use identity_iota::credential::Credential;
+use identity_iota::credential::CredentialBuilder;
+use identity_iota::credential::Subject;
+use identity_iota::crypto::KeyPair;
+use identity_iota::crypto::KeyType;
+use identity_iota::crypto::ProofOptions;
+use identity_iota::did::DIDUrl;
+use identity_iota::did::DID;
+use identity_iota::iota_core::IotaDocument;
+
+use ::RevocationList2020::Envelope::{encode_credential, Compression};
+use ::RevocationList2020::BitOrder;
+use ::RevocationList2020::RevocationList2020;
+use ::RevocationList2020::RevocationList2020Credential::RevocationList2020Credential;
+use ::RevocationList2020::RevocationList2020Status::RevocationList2020Status;
+
+//Issuer locale con chiave e DID document generati al volo, senza accesso alla rete
+pub struct Issuer
+{
+    pub keypair: KeyPair,
+    pub document: IotaDocument,
+}
+
+impl Issuer
+{
+    pub fn new() -> Self
+    {
+        let keypair = KeyPair::new(KeyType::Ed25519).unwrap();
+        let document = IotaDocument::new(&keypair).unwrap();
+        Issuer { keypair, document }
+    }
+
+    //Firma `credential` con la chiave dell'issuer
+    pub fn sign(&self, credential: &mut Credential)
+    {
+        self.document
+            .sign_data(credential, self.keypair.private(), self.document.default_signing_method().unwrap().id(), ProofOptions::default())
+            .unwrap();
+    }
+
+    //Lista firmata con gli indici `revoked` revocati ed emessa in `date`
+    pub fn signed(&self, list_id: &str, revoked: &[u64], date: &str) -> Credential
+    {
+        self.signed_with_order(list_id, revoked, date, BitOrder::Msb0)
+    }
+
+    //Come `signed`, con gli indici revocati scritti nell'ordine dei bit `order`
+    pub fn signed_with_order(&self, list_id: &str, revoked: &[u64], date: &str, order: BitOrder) -> Credential
+    {
+        let mut rl = RevocationList2020::new(list_id, 16).unwrap().with_bit_order(order);
+        rl.revoke_many(revoked.to_vec()).unwrap();
+
+        let issuer = Url::parse(self.document.id().as_str()).unwrap();
+        let mut credential = RevocationList2020Credential::new(issuer, rl).unwrap().into_credential();
+        credential.issuance_date = Timestamp::parse(date).unwrap();
+        self.sign(&mut credential);
+        credential
+    }
+
+    //Payload pubblicato per la lista firmata
+    pub fn list(&self, list_id: &str, revoked: &[u64], date: &str) -> Vec<u8>
+    {
+        encode_credential(&self.signed(list_id, revoked, date), Compression::None).unwrap()
+    }
+
+    //Payload pubblicato per una lista legacy, con i bit di ogni byte indirizzati dal meno significativo
+    pub fn legacy_list(&self, list_id: &str, revoked: &[u64], date: &str) -> Vec<u8>
+    {
+        encode_credential(&self.signed_with_order(list_id, revoked, date, BitOrder::Lsb0), Compression::None).unwrap()
+    }
+
+    //Credenziale revocabile emessa da questo issuer, con indice `index` nella lista `list_id`
+    pub fn credential(&self, index: u64, list_id: &str) -> Credential
+    {
+        let status = RevocationList2020Status::new(DIDUrl::new(self.document.id().clone(), None), index, Url::parse(list_id).unwrap());
+        CredentialBuilder::default()
+            .issuer(Url::parse(self.document.id().as_str()).unwrap())
+            .subject(Subject::from_json_value(json!({ "id": "did:example:alice", "name": "Alice" })).unwrap())
+            .status(status.into())
+            .build()
+            .unwrap()
+    }
+}
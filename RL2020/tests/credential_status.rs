@@ -0,0 +1,126 @@
+#![allow(non_snake_case)]
+
+use identity_core::common::Url;
+use identity_core::convert::FromJson;
+use identity_core::json;
+use identity_credential::credential::Credential;
+use identity_credential::credential::CredentialBuilder;
+use identity_credential::credential::Status;
+use identity_credential::credential::Subject;
+use identity_did::did::CoreDID;
+use identity_did::did::DIDUrl;
+
+use ::RevocationList2020::CredentialError;
+use ::RevocationList2020::CredentialStatus::{status_reference, status_references};
+use ::RevocationList2020::RevocationList2020Status::RevocationList2020Status;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+const ISSUER: &str = "did:iota:FGZTMJSQZoGJxE416TmFnEjCDRJWFE5uTmEMEm5vBypv";
+
+fn credential(status: Option<Status>) -> Credential
+{
+    let mut builder = CredentialBuilder::default()
+        .issuer(Url::parse(ISSUER).unwrap())
+        .subject(Subject::from_json_value(json!({ "id": "did:example:alice", "name": "Alice" })).unwrap());
+    if let Some(status) = status
+    {
+        builder = builder.status(status);
+    }
+    builder.build().unwrap()
+}
+
+#[test]
+fn extracts_index_and_list_credential()
+{
+    let did = CoreDID::parse(ISSUER).unwrap();
+    let status = RevocationList2020Status::new(DIDUrl::new(did, None), 2500, Url::parse(LIST_ID).unwrap());
+
+    assert_eq!(status.list_credential().as_str(), LIST_ID);
+
+    let reference = status_reference(&credential(Some(status.into()))).unwrap();
+    assert_eq!(reference.index(), 2500);
+    assert_eq!(reference.list_credential().as_str(), LIST_ID);
+}
+
+#[test]
+fn reports_missing_and_malformed_status()
+{
+    assert!(matches!(status_reference(&credential(None)), Err(CredentialError::MissingStatus)));
+
+    let bad_index = Status::from_json_value(json!({
+        "id": ISSUER,
+        "type": "RevocationList2020",
+        "revocationListIndex": "seven",
+        "revocationListCredential": LIST_ID,
+    }))
+    .unwrap();
+    assert!(matches!(status_reference(&credential(Some(bad_index))), Err(CredentialError::InvalidStatus(_))));
+
+    let bad_url = Status::from_json_value(json!({
+        "id": ISSUER,
+        "type": "RevocationList2020",
+        "revocationListIndex": "7",
+        "revocationListCredential": "not a url",
+    }))
+    .unwrap();
+    assert!(RevocationList2020Status::try_from(bad_url.clone()).is_err());
+    assert!(matches!(status_reference(&credential(Some(bad_url))), Err(CredentialError::InvalidStatus(_))));
+}
+
+#[test]
+fn handles_multiple_status_entries()
+{
+    let json = json!({
+        "credentialStatus": [
+            {
+                "id": "https://example.com/credentials/status/4#94567",
+                "type": "StatusList2021Entry",
+                "statusPurpose": "suspension",
+                "statusListIndex": "94567",
+                "statusListCredential": "https://example.com/credentials/status/4"
+            },
+            {
+                "id": ISSUER,
+                "type": "RevocationList2020",
+                "revocationListIndex": "7",
+                "revocationListCredential": LIST_ID
+            }
+        ]
+    });
+
+    let references = status_references(&json).unwrap();
+    assert_eq!(references.len(), 1);
+    assert_eq!(references[0].index(), 7);
+
+    assert!(matches!(status_references(&json!({})), Err(CredentialError::MissingStatus)));
+}
+
+#[test]
+fn accepts_string_and_number_indices_beyond_u32()
+{
+    let large = u64::from(u32::MAX) + 10;
+
+    for index in [json!(large.to_string()), json!(large)]
+    {
+        let status = Status::from_json_value(json!({
+            "id": ISSUER,
+            "type": "RevocationList2020",
+            "revocationListIndex": index,
+            "revocationListCredential": LIST_ID,
+        }))
+        .unwrap();
+        assert_eq!(status_reference(&credential(Some(status))).unwrap().index(), large);
+    }
+
+    for index in [json!(-1), json!(2.5), json!("18446744073709551616")]
+    {
+        let status = Status::from_json_value(json!({
+            "id": ISSUER,
+            "type": "RevocationList2020",
+            "revocationListIndex": index,
+            "revocationListCredential": LIST_ID,
+        }))
+        .unwrap();
+        assert!(matches!(RevocationList2020Status::try_from(status), Err(CredentialError::InvalidStatus(_))));
+    }
+}
@@ -0,0 +1,74 @@
+#![allow(non_snake_case)]
+
+use ::RevocationList2020::Bitstring::{Bitstring, Codec, EncodedList};
+use ::RevocationList2020::RevocationList2020;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+#[test]
+fn updates_defer_encoding_until_encode()
+{
+    let mut rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+    let empty = rl.get_encList().unwrap();
+    assert!(!rl.is_dirty());
+
+    rl.revoke(7).unwrap();
+    rl.revoke(2500).unwrap();
+    assert!(rl.is_dirty());
+
+    //get_encList non usa la cache obsoleta
+    let fresh = rl.get_encList().unwrap();
+    assert_ne!(fresh, empty);
+    assert!(rl.is_dirty());
+
+    assert_eq!(rl.encode().unwrap(), fresh);
+    assert!(!rl.is_dirty());
+
+    let decoded = RevocationList2020::new_withList(LIST_ID, &fresh).unwrap();
+    assert!(decoded.is_revoked(7).unwrap());
+    assert!(decoded.is_revoked(2500).unwrap());
+}
+
+#[test]
+fn serde_round_trip_keeps_pending_revocations()
+{
+    let mut rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+    rl.revoke(7).unwrap();
+
+    //Serializzata senza chiamare encode(): la encodedList salvata è ancora quella vuota
+    let json = serde_json::to_string(&rl).unwrap();
+    let loaded: RevocationList2020 = serde_json::from_str(&json).unwrap();
+    assert!(loaded.is_dirty());
+    assert!(loaded.is_revoked(7).unwrap());
+    assert_eq!(loaded.get_encList().unwrap(), rl.get_encList().unwrap());
+
+    let decoded = RevocationList2020::new_withList(LIST_ID, &loaded.get_encList().unwrap()).unwrap();
+    assert!(decoded.is_revoked(7).unwrap());
+}
+
+#[test]
+fn encoded_lists_track_changes_for_every_codec()
+{
+    for codec in [Codec::ZlibBase64, Codec::GzipBase64Url, Codec::GzipMultibase]
+    {
+        let mut list = EncodedList::new(Bitstring::new(16 * 1024), codec).unwrap();
+        let empty = list.get_encList().unwrap();
+
+        //Scritture che non cambiano nulla lasciano valida la cache
+        assert!(!list.set(7, false).unwrap());
+        assert_eq!(list.set_bits(8, 4, 0).unwrap(), 0);
+        assert!(list.apply(vec![(9, true), (9, false)]).unwrap().is_empty());
+        assert!(!list.is_dirty());
+
+        assert_eq!(list.apply(vec![(7, true), (3, true)]).unwrap(), vec![3, 7]);
+        assert!(list.is_dirty());
+        let packed = list.bits().pack(codec).unwrap();
+        assert_eq!(list.encode().unwrap(), packed);
+        assert!(!list.is_dirty());
+
+        let decoded = EncodedList::unpack(&list.get_encList().unwrap(), codec).unwrap();
+        assert_eq!(decoded.bits(), list.bits());
+        assert_eq!(decoded.codec(), codec);
+        assert_ne!(decoded.get_encList().unwrap(), empty);
+    }
+}
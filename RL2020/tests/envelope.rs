@@ -0,0 +1,70 @@
+#![allow(non_snake_case)]
+
+use identity_core::common::Url;
+
+use ::RevocationList2020::CredentialError;
+use ::RevocationList2020::Envelope::*;
+use ::RevocationList2020::RevocationList2020;
+use ::RevocationList2020::RevocationList2020Credential::RevocationList2020Credential;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+const ISSUER: &str = "did:iota:FGZTMJSQZoGJxE416TmFnEjCDRJWFE5uTmEMEm5vBypv";
+
+#[test]
+fn bodies_round_trip_byte_exactly()
+{
+    //Body con '{' e byte non UTF-8, che il vecchio split su '{' corrompeva
+    let body: Vec<u8> = b"{\"a\":{\"b\":1}}".iter().copied().chain([0xff, 0x00, b'{', 0x80]).collect();
+
+    for compression in [Compression::None, Compression::Gzip]
+    {
+        let bytes = encode_envelope(ContentType::CredentialJson, compression, &body).unwrap();
+        assert_eq!(bytes[..3], [ENVELOPE_VERSION, 1, compression as u8]);
+
+        let envelope = decode_envelope(&bytes).unwrap();
+        assert_eq!(envelope, Envelope { content_type: ContentType::CredentialJson, compression, body: body.clone() });
+    }
+}
+
+#[test]
+fn list_credentials_round_trip()
+{
+    let mut rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+    rl.revoke_many(vec![3, 300, 131071]).unwrap();
+    let credential = RevocationList2020Credential::new(Url::parse(ISSUER).unwrap(), rl).unwrap().into_credential();
+
+    let decoded = decode_credential(&encode_credential(&credential, Compression::Gzip).unwrap()).unwrap();
+    assert_eq!(decoded, credential);
+    assert!(RevocationList2020Credential::try_from(decoded).unwrap().list().is_revoked(300).unwrap());
+}
+
+#[test]
+fn malformed_envelopes_are_rejected()
+{
+    let bytes = encode_envelope(ContentType::CredentialJson, Compression::Gzip, b"{}").unwrap();
+    let invalid = |bytes: &[u8]| matches!(decode_envelope(bytes), Err(CredentialError::InvalidEnvelope(_)));
+
+    //Troppo corto, versione/content type/compressione sconosciuti
+    assert!(invalid(&bytes[..4]));
+    for (pos, value) in [(0, 2), (1, 0), (2, 9)]
+    {
+        let mut altered = bytes.clone();
+        altered[pos] = value;
+        assert!(invalid(&altered));
+    }
+
+    //Lunghezza dichiarata diversa dal body
+    assert!(invalid(&bytes[..bytes.len() - 1]));
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(invalid(&trailing));
+
+    //JSON grezzo pubblicato senza envelope
+    assert!(invalid(b"{\"id\":\"https://example.com\"}"));
+
+    //Body compresso corrotto
+    let mut corrupted = bytes.clone();
+    let last = corrupted.len() - 1;
+    corrupted[last] ^= 0xff;
+    assert!(matches!(decode_envelope(&corrupted), Err(CredentialError::Decoding { .. })));
+}
@@ -0,0 +1,65 @@
+#![allow(non_snake_case)]
+
+use ::RevocationList2020::{CodingStage, CredentialError, RevocationList2020};
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+#[test]
+fn out_of_range_reports_index_and_capacity()
+{
+    let mut rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+
+    match rl.revoke(131072)
+    {
+        Err(CredentialError::IndexOutOfRange { index, capacity }) =>
+        {
+            assert_eq!(index, 131072);
+            assert_eq!(capacity, 131072);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn size_and_id_are_validated()
+{
+    assert!(matches!(
+        RevocationList2020::new(LIST_ID, 8),
+        Err(CredentialError::InvalidSize { size: 8, min: 16, max: 128 })
+    ));
+    assert!(matches!(
+        RevocationList2020::new(LIST_ID, 256),
+        Err(CredentialError::InvalidSize { size: 256, min: 16, max: 128 })
+    ));
+    assert!(matches!(RevocationList2020::new(" ", 16), Err(CredentialError::EmptyId)));
+}
+
+#[test]
+fn decoding_reports_failed_stage()
+{
+    assert!(matches!(
+        RevocationList2020::new_withList(LIST_ID, "not base64!"),
+        Err(CredentialError::Decoding { stage: CodingStage::Base64, .. })
+    ));
+    assert!(matches!(
+        RevocationList2020::new_withList(LIST_ID, "aGVsbG8="),
+        Err(CredentialError::Decoding { stage: CodingStage::Zlib, .. })
+    ));
+}
+
+#[test]
+fn indices_beyond_the_list_are_rejected_without_truncation()
+{
+    let mut rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+
+    //Con un cast `as usize` a 32 bit questo indice diventerebbe 0
+    let index = 1u64 << 32;
+    match rl.revoke(index)
+    {
+        Err(CredentialError::IndexOutOfRange { index: i, capacity }) => assert_eq!((i, capacity), (index, 131072)),
+        Err(CredentialError::IndexNotAddressable { index: i }) => assert_eq!(i, index),
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert!(!rl.is_revoked(0).unwrap());
+    assert!(rl.is_revoked(u64::MAX).is_err());
+}
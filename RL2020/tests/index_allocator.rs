@@ -0,0 +1,142 @@
+#![allow(non_snake_case)]
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use ::RevocationList2020::CredentialError;
+use ::RevocationList2020::IndexAllocator::{AllocationStrategy, IndexAllocator};
+use ::RevocationList2020::IssuerStore::{FileStore, IssuerStore, ListRecord, SqliteStore};
+use ::RevocationList2020::RevocationList2020;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+fn temp_dir(name: &str) -> PathBuf
+{
+    let dir = std::env::temp_dir().join(format!("rl2020-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn hands_out_unused_indices_and_refuses_double_allocation()
+{
+    let rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+    let mut allocator = IndexAllocator::new(&rl);
+    assert_eq!(allocator.capacity(), 131072);
+
+    allocator.allocate_index(1).unwrap();
+    assert_eq!([allocator.allocate().unwrap(), allocator.allocate().unwrap()], [0, 2]);
+    assert!(matches!(allocator.allocate_index(2), Err(CredentialError::IndexAlreadyAllocated(2))));
+    assert!(matches!(allocator.allocate_index(131072), Err(CredentialError::IndexOutOfRange { .. })));
+    assert_eq!((allocator.allocated(), allocator.remaining()), (3, 131069));
+}
+
+#[test]
+fn signals_when_a_new_list_is_needed()
+{
+    let mut allocator = IndexAllocator::with_capacity(LIST_ID, 3);
+    for expected in 0..3
+    {
+        assert!(!allocator.is_full());
+        assert_eq!(allocator.allocate().unwrap(), expected);
+    }
+    assert!(allocator.is_full());
+    assert!(matches!(allocator.allocate(), Err(CredentialError::ListFull { capacity: 3, .. })));
+}
+
+#[test]
+fn allocations_survive_a_restart()
+{
+    let root = temp_dir("allocator");
+    let stores: [Box<dyn IssuerStore>; 2] = [Box::new(FileStore::new(&root)), Box::new(SqliteStore::open_in_memory().unwrap())];
+
+    for store in &stores
+    {
+        //Le assegnazioni vengono salvate insieme alla lista, in un'unica scrittura
+        let mut record = ListRecord::new(RevocationList2020::new(LIST_ID, 16).unwrap());
+        for _ in 0..10
+        {
+            record.allocator.allocate().unwrap();
+        }
+        record.allocator.allocate_index(500).unwrap();
+        store.save(&record).unwrap();
+
+        let mut reopened = store.load(LIST_ID).unwrap().unwrap().allocator;
+        assert_eq!(reopened.allocated(), 11);
+        assert!(reopened.is_allocated(500));
+        assert_eq!(reopened.allocate().unwrap(), 10);
+        assert!(matches!(reopened.allocate_index(500), Err(CredentialError::IndexAlreadyAllocated(500))));
+    }
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn crosses_word_boundaries()
+{
+    let mut allocator = IndexAllocator::with_capacity(LIST_ID, 130);
+    for index in (0..64).chain(65..129)
+    {
+        allocator.allocate_index(index).unwrap();
+    }
+    assert_eq!(allocator.allocated_ranges(), vec![(0, 63), (65, 128)]);
+    assert_eq!([allocator.allocate().unwrap(), allocator.allocate().unwrap()], [64, 129]);
+    assert!(allocator.is_full());
+    assert!(!allocator.is_allocated(130));
+
+    //Ricostruzione dagli intervalli, come al caricamento di un record
+    let rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+    let mut rebuilt = IndexAllocator::from_ranges(&rl, &[(0, 63), (65, 5000), (131071, 131071)]).unwrap();
+    assert_eq!(rebuilt.allocated_ranges(), vec![(0, 63), (65, 5000), (131071, 131071)]);
+    assert_eq!((rebuilt.allocated(), rebuilt.allocate().unwrap(), rebuilt.allocate().unwrap()), (5001, 64, 5001));
+    assert!(matches!(IndexAllocator::from_ranges(&rl, &[(0, 70), (64, 64)]), Err(CredentialError::IndexAlreadyAllocated(64))));
+    assert!(matches!(IndexAllocator::from_ranges(&rl, &[(5, 131072)]), Err(CredentialError::IndexOutOfRange { .. })));
+}
+
+#[test]
+fn random_assignment_is_seedable_and_never_repeats()
+{
+    let random = |seed| IndexAllocator::with_capacity(LIST_ID, 1000).with_strategy(AllocationStrategy::Random).with_seed(seed);
+
+    let sequence = |seed| -> Vec<u64>
+    {
+        let mut allocator = random(seed);
+        (0..20).map(|_| allocator.allocate().unwrap()).collect()
+    };
+
+    let first = sequence(7);
+    assert_eq!(first, sequence(7));
+    assert_ne!(first, sequence(8));
+    assert_ne!(first, (0..20).collect::<Vec<u64>>());
+
+    //Fino all'ultimo indice libero, senza ripetizioni e rispettando quelli già assegnati
+    let mut allocator = random(1);
+    allocator.allocate_index(999).unwrap();
+    let mut seen: BTreeSet<u64> = BTreeSet::new();
+    while !allocator.is_full()
+    {
+        assert!(seen.insert(allocator.allocate().unwrap()));
+    }
+    assert_eq!(seen.len(), 999);
+    assert!(!seen.contains(&999));
+    assert!(matches!(allocator.allocate(), Err(CredentialError::ListFull { .. })));
+}
+
+#[test]
+fn random_assignment_finds_the_last_free_indices()
+{
+    //Due blocchi di conteggio, con pochi indici liberi sparsi in entrambi
+    let free = [0, 63, 64, 4095, 4096, 4999];
+    let mut allocator = IndexAllocator::with_capacity(LIST_ID, 5000).with_strategy(AllocationStrategy::Random).with_seed(3);
+    for index in (0..5000).filter(|index| !free.contains(index))
+    {
+        allocator.allocate_index(index).unwrap();
+    }
+
+    let mut seen: BTreeSet<u64> = BTreeSet::new();
+    while !allocator.is_full()
+    {
+        assert!(seen.insert(allocator.allocate().unwrap()));
+    }
+    assert_eq!(seen, free.into_iter().collect());
+}
@@ -0,0 +1,47 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use identity_iota::core::ToJson;
+
+use ::RevocationList2020::BitOrder;
+use ::RevocationList2020::CredentialError;
+use ::RevocationList2020::Inspect::{inspect, inspect_encoded_list};
+use ::RevocationList2020::RevocationList2020;
+
+use common::Issuer;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+#[test]
+fn reports_revoked_ranges_and_compression()
+{
+    let mut list = RevocationList2020::new(LIST_ID, 16).unwrap();
+    list.revoke_many(vec![3, 4, 5, 90, 131071]).unwrap();
+    let encoded = list.encode().unwrap().to_owned();
+
+    let report = inspect_encoded_list(&encoded, BitOrder::Msb0).unwrap();
+    assert_eq!(report.id, None);
+    assert_eq!((report.size_kb, report.capacity, report.revoked), (16, 131072, 5));
+    assert_eq!(report.ranges, vec![(3, 5), (90, 90), (131071, 131071)]);
+    assert_eq!((report.encoded_len, report.bitstring_len), (encoded.len(), 16384));
+    assert!(report.compressed_len < report.encoded_len && report.compression_ratio > 100.0);
+    assert!(report.to_string().contains("3-5, 90, 131071"));
+
+    //Letta con l'ordine dei bit sbagliato la stessa lista ha altri indici
+    assert_eq!(inspect_encoded_list(&encoded, BitOrder::Lsb0).unwrap().ranges[0], (2, 4));
+}
+
+#[test]
+fn accepts_a_list_credential()
+{
+    let issuer = Issuer::new();
+    let credential = issuer.signed(LIST_ID, &[7, 8], "2022-01-01T00:00:00Z").to_json().unwrap();
+
+    let report = inspect(&credential, BitOrder::Msb0).unwrap();
+    assert_eq!(report.id.as_deref(), Some(LIST_ID));
+    assert_eq!(report.ranges, vec![(7, 8)]);
+
+    assert!(matches!(inspect("{\"id\": 1}", BitOrder::Msb0), Err(CredentialError::InvalidListCredential(_))));
+    assert!(matches!(inspect("not base64!", BitOrder::Msb0), Err(CredentialError::Decoding { .. })));
+}
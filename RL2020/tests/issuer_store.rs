@@ -0,0 +1,174 @@
+#![allow(non_snake_case)]
+
+use std::path::PathBuf;
+
+use identity_core::common::Url;
+
+use ::RevocationList2020::CredentialError;
+use ::RevocationList2020::IndexAllocator::AllocationStrategy;
+use ::RevocationList2020::IssuerStore::{FileStore, IssuerStore, ListRecord, SqliteStore};
+use ::RevocationList2020::ListManager::ListManager;
+use ::RevocationList2020::RevocationList2020;
+
+const BASE_ID: &str = "https://example.com/credentials/status";
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+fn temp_dir(name: &str) -> PathBuf
+{
+    let dir = std::env::temp_dir().join(format!("rl2020-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+//Esercita un backend qualsiasi tramite il solo trait
+fn save_and_reload<T: IssuerStore>(store: &T)
+{
+    assert!(store.load(LIST_ID).unwrap().is_none());
+
+    let mut record = ListRecord::new(RevocationList2020::new(LIST_ID, 16).unwrap());
+    record.allocator = record.allocator.with_strategy(AllocationStrategy::Random);
+    for index in [3, 4, 5, 90]
+    {
+        record.allocator.allocate_index(index).unwrap();
+    }
+    record.credentials.insert(4, "urn:uuid:alice".to_owned());
+    record.list.revoke_many(vec![4, 90]).unwrap();
+    store.save(&record).unwrap();
+
+    //Le revoche non ancora codificate vengono salvate comunque
+    let loaded = store.load(LIST_ID).unwrap().unwrap();
+    assert!(loaded.list.is_revoked(4).unwrap() && loaded.list.is_revoked(90).unwrap());
+    assert!(!loaded.list.is_revoked(3).unwrap());
+    assert_eq!(loaded.allocator.allocated_ranges(), vec![(3, 5), (90, 90)]);
+    assert_eq!(loaded.allocator.strategy(), AllocationStrategy::Random);
+    assert_eq!(loaded.credentials.get(&4).map(String::as_str), Some("urn:uuid:alice"));
+    assert!(loaded.needs_publication());
+
+    //Un nuovo salvataggio sostituisce il precedente
+    record.list.reset(4).unwrap();
+    store.save(&record).unwrap();
+    assert!(!store.load(LIST_ID).unwrap().unwrap().list.is_revoked(4).unwrap());
+
+    store.save(&ListRecord::new(RevocationList2020::new("https://example.com/credentials/status/1", 16).unwrap())).unwrap();
+    assert_eq!(store.list_ids().unwrap(), vec!["https://example.com/credentials/status/1", LIST_ID]);
+
+    assert!(store.remove(LIST_ID).unwrap());
+    assert!(!store.remove(LIST_ID).unwrap());
+    assert!(store.load(LIST_ID).unwrap().is_none());
+}
+
+#[test]
+fn file_store_saves_and_reloads()
+{
+    let root = temp_dir("file-store");
+    save_and_reload(&FileStore::new(&root));
+
+    //Un file temporaneo lasciato da un crash non è un record
+    std::fs::write(root.join(".1-0.tmp"), b"{\"version\":").unwrap();
+    assert_eq!(FileStore::new(&root).list_ids().unwrap().len(), 1);
+
+    //Id più lunghi del nome massimo di un file
+    let store = FileStore::new(&root);
+    let long_id = format!("{}/{}", BASE_ID, "a".repeat(300));
+    store.save(&ListRecord::new(RevocationList2020::new(&long_id, 16).unwrap())).unwrap();
+    assert_eq!(store.load(&long_id).unwrap().unwrap().list_id(), long_id);
+    assert!(store.list_ids().unwrap().contains(&long_id));
+    assert!(store.remove(&long_id).unwrap());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn sqlite_store_saves_and_reloads()
+{
+    save_and_reload(&SqliteStore::open_in_memory().unwrap());
+
+    let root = temp_dir("sqlite-store");
+    std::fs::create_dir_all(&root).unwrap();
+    let path = root.join("issuer.db");
+    save_and_reload(&SqliteStore::open(&path).unwrap());
+
+    //Il database sopravvive alla chiusura della connessione
+    assert_eq!(SqliteStore::open(&path).unwrap().list_ids().unwrap().len(), 1);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn rejects_corrupted_records()
+{
+    let root = temp_dir("file-store-corrupted");
+    let store = FileStore::new(&root);
+    let mut record = ListRecord::new(RevocationList2020::new(LIST_ID, 16).unwrap());
+    record.allocator.allocate_index(1).unwrap();
+    store.save(&record).unwrap();
+
+    let path = std::fs::read_dir(&root).unwrap().next().unwrap().unwrap().path();
+    let json = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, json.replace("\"allocated\":[[1,1]]", "\"allocated\":[]").replace("\"credentials\":{}", "\"credentials\":{\"1\":\"urn:uuid:alice\"}")).unwrap();
+
+    assert!(matches!(store.load(LIST_ID), Err(CredentialError::Storage(_))));
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn list_manager_resumes_from_the_store()
+{
+    let store = SqliteStore::open_in_memory().unwrap();
+
+    let mut manager = ListManager::new(BASE_ID, 16).unwrap();
+    let (list_id, index) = manager.allocate("urn:uuid:alice", &store).unwrap();
+    manager.allocate("urn:uuid:bob", &store).unwrap();
+    manager.revoke("urn:uuid:alice").unwrap();
+    manager.save(&store).unwrap();
+
+    //Dopo il riavvio la revoca c'è ancora e gli indici già assegnati non vengono riassegnati
+    let mut restarted = ListManager::open(BASE_ID, 16, &store).unwrap();
+    assert_eq!(restarted.slot("urn:uuid:alice"), Some((list_id.as_str(), index)));
+    assert!(restarted.list(&list_id).unwrap().is_revoked(index).unwrap());
+    assert_eq!(restarted.dirty_lists(), vec![list_id.as_str()]);
+    assert_eq!(restarted.allocate("urn:uuid:carol", &store).unwrap(), (list_id.clone(), 2));
+    assert!(matches!(restarted.allocate("urn:uuid:bob", &store), Err(CredentialError::CredentialAlreadyAllocated(_))));
+
+    //Senza liste salvate si parte da una lista vuota
+    let fresh = ListManager::open(BASE_ID, 16, &SqliteStore::open_in_memory().unwrap()).unwrap();
+    assert_eq!(fresh.lists().count(), 1);
+}
+
+#[test]
+fn allocations_are_saved_before_they_are_returned()
+{
+    let root = temp_dir("file-store-allocate");
+    let store = FileStore::new(&root);
+
+    //Nessuna chiamata a save: il processo si interrompe subito dopo aver consegnato gli indici
+    let mut manager = ListManager::new(BASE_ID, 16).unwrap();
+    let alice = manager.allocate("urn:uuid:alice", &store).unwrap();
+    let bob = manager.allocate("urn:uuid:bob", &store).unwrap();
+    drop(manager);
+
+    let mut restarted = ListManager::open(BASE_ID, 16, &store).unwrap();
+    assert_eq!(restarted.slot("urn:uuid:alice"), Some((alice.0.as_str(), alice.1)));
+    assert_eq!(restarted.slot("urn:uuid:bob"), Some((bob.0.as_str(), bob.1)));
+    let carol = restarted.allocate("urn:uuid:carol", &store).unwrap();
+    assert!(carol != alice && carol != bob);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn records_stay_pending_until_published()
+{
+    let issuer = Url::parse("did:example:issuer").unwrap();
+    let mut record = ListRecord::new(RevocationList2020::new(LIST_ID, 16).unwrap());
+    let credential = record.list_credential(&issuer).unwrap();
+    record.set_published(&credential, "publication-1");
+    assert!(!record.needs_publication());
+
+    //Revoca codificata nella nuova VC lista, ma pubblicazione mai confermata
+    record.list.revoke(4).unwrap();
+    let next = record.list_credential(&issuer).unwrap();
+    assert!(next.issuance_date > credential.issuance_date);
+    assert!(!record.list.is_dirty());
+    assert!(record.needs_publication());
+}
@@ -0,0 +1,53 @@
+#![allow(non_snake_case)]
+
+use ::RevocationList2020::BitOrder;
+use ::RevocationList2020::RevocationList2020;
+use ::RevocationList2020::RevocationStatus;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+//Indici ai bordi dei byte e delle parole da 8 byte, più l'ultimo della lista
+const REVOKED: [u64; 12] = [0, 1, 2, 7, 8, 63, 64, 65, 500, 1000, 1001, 131071];
+
+fn list(order: BitOrder) -> RevocationList2020
+{
+    let mut list = RevocationList2020::new(LIST_ID, 16).unwrap().with_bit_order(order);
+    list.revoke_many(REVOKED.to_vec()).unwrap();
+    list
+}
+
+#[test]
+fn iterators_match_point_queries()
+{
+    for order in [BitOrder::Msb0, BitOrder::Lsb0]
+    {
+        let list = list(order);
+        let expected: Vec<u64> = (0..list.capacity() as u64).filter(|i| list.is_revoked(*i).unwrap()).collect();
+
+        assert_eq!(list.iter_revoked().collect::<Vec<u64>>(), expected);
+        assert_eq!(expected, REVOKED.to_vec());
+        assert_eq!(list.count_revoked(), REVOKED.len() as u64);
+
+        let statuses: Vec<(u64, RevocationStatus)> = list.iter().collect();
+        assert_eq!(statuses.len(), list.capacity());
+        assert!(statuses.iter().all(|(index, status)| *status == list.get(*index).unwrap()));
+    }
+}
+
+#[test]
+fn set_ranges_merge_consecutive_indices()
+{
+    let list = list(BitOrder::Msb0);
+    assert_eq!(
+        list.iter_set_ranges().collect::<Vec<(u64, u64)>>(),
+        vec![(0, 2), (7, 8), (63, 65), (500, 500), (1000, 1001), (131071, 131071)]
+    );
+
+    let empty = RevocationList2020::new(LIST_ID, 16).unwrap();
+    assert_eq!((empty.iter_revoked().next(), empty.iter_set_ranges().next(), empty.count_revoked()), (None, None, 0));
+
+    let mut full = RevocationList2020::new(LIST_ID, 16).unwrap();
+    full.revoke_many(0..131072).unwrap();
+    assert_eq!(full.iter_set_ranges().collect::<Vec<(u64, u64)>>(), vec![(0, 131071)]);
+    assert_eq!(full.count_revoked(), 131072);
+}
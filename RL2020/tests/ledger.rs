@@ -0,0 +1,83 @@
+#![allow(non_snake_case)]
+
+use std::path::PathBuf;
+
+use ::RevocationList2020::Ledger::{FileLedger, MemoryLedger, StatusListPublisher, StatusListSource};
+
+const INDEX: &str = "RL2020_MyList";
+
+//Esercita un ledger qualsiasi tramite i soli trait, come fanno issuer e verifier
+async fn publish_and_fetch<L: StatusListPublisher + StatusListSource>(ledger: &L)
+{
+    assert!(ledger.fetch(INDEX).await.unwrap().is_empty());
+
+    let first = ledger.publish(INDEX, b"{\"v\":1}").await.unwrap();
+    let second = ledger.publish(INDEX, b"{\"v\":2}").await.unwrap();
+    ledger.publish("other/index", b"other").await.unwrap();
+    assert_ne!(first, second);
+
+    let publications = ledger.fetch(INDEX).await.unwrap();
+    assert_eq!(publications.len(), 2);
+    assert_eq!((publications[0].id.as_str(), publications[0].payload.as_slice()), (first.as_str(), &b"{\"v\":1}"[..]));
+    assert_eq!((publications[1].id.as_str(), publications[1].payload.as_slice()), (second.as_str(), &b"{\"v\":2}"[..]));
+
+    assert_eq!(ledger.fetch("other/index").await.unwrap()[0].payload, b"other");
+}
+
+fn temp_dir(name: &str) -> PathBuf
+{
+    let dir = std::env::temp_dir().join(format!("rl2020-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[tokio::test]
+async fn memory_ledger_keeps_publication_order()
+{
+    let ledger = MemoryLedger::new();
+    publish_and_fetch(&ledger).await;
+
+    //I cloni condividono le stesse pubblicazioni
+    assert_eq!(ledger.clone().fetch(INDEX).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn file_ledger_persists_publications()
+{
+    let root = temp_dir("file-ledger");
+    publish_and_fetch(&FileLedger::new(&root)).await;
+
+    //Una nuova istanza sulla stessa directory vede le pubblicazioni precedenti e non le sovrascrive
+    let reopened = FileLedger::new(&root);
+    reopened.publish(INDEX, b"{\"v\":3}").await.unwrap();
+    let payloads: Vec<Vec<u8>> = reopened.fetch(INDEX).await.unwrap().into_iter().map(|p| p.payload).collect();
+    assert_eq!(payloads, vec![b"{\"v\":1}".to_vec(), b"{\"v\":2}".to_vec(), b"{\"v\":3}".to_vec()]);
+
+    //Nessun file temporaneo rimane accanto alle pubblicazioni
+    for dir in std::fs::read_dir(&root).unwrap()
+    {
+        for file in std::fs::read_dir(dir.unwrap().path()).unwrap()
+        {
+            assert_eq!(file.unwrap().path().extension().unwrap(), "msg");
+        }
+    }
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test]
+async fn file_ledger_accepts_long_indices()
+{
+    //Gli indici sono gli URL delle liste: possono superare la lunghezza massima di un nome di file
+    let root = temp_dir("file-ledger-long");
+    let ledger = FileLedger::new(&root);
+    let long = format!("https://example.com/credentials/status/{}", "a".repeat(300));
+    let other = format!("{}b", long);
+
+    ledger.publish(&long, b"long").await.unwrap();
+    ledger.publish(&other, b"other").await.unwrap();
+    assert_eq!(ledger.fetch(&long).await.unwrap()[0].payload, b"long");
+    assert_eq!(ledger.fetch(&other).await.unwrap()[0].payload, b"other");
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
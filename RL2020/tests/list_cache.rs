@@ -0,0 +1,232 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use identity_iota::core::Timestamp;
+use identity_iota::core::Value;
+
+use ::RevocationList2020::BitOrder;
+use ::RevocationList2020::Ledger::{MemoryLedger, Publication, StatusListPublisher, StatusListSource};
+use ::RevocationList2020::ListCache::{max_age, ListCache};
+use ::RevocationList2020::Result;
+use ::RevocationList2020::Verifier::StatusVerdict;
+
+use common::Issuer;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+const DATE: &str = "2022-01-01T00:00:00Z";
+
+//Sorgente che conta le letture dal ledger, separando i download dalle sole letture degli id
+#[derive(Clone, Default)]
+struct CountingSource
+{
+    ledger: MemoryLedger,
+    fetches: Arc<AtomicUsize>,
+    lookups: Arc<AtomicUsize>,
+}
+
+impl CountingSource
+{
+    fn fetches(&self) -> usize
+    {
+        self.fetches.load(Ordering::SeqCst)
+    }
+
+    fn lookups(&self) -> usize
+    {
+        self.lookups.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl StatusListSource for CountingSource
+{
+    async fn fetch(&self, index: &str) -> Result<Vec<Publication>>
+    {
+        self.fetches.fetch_add(1, Ordering::SeqCst);
+        self.ledger.fetch(index).await
+    }
+
+    async fn publication_ids(&self, index: &str) -> Result<Vec<String>>
+    {
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+        self.ledger.publication_ids(index).await
+    }
+}
+
+fn list_id(n: usize) -> String
+{
+    format!("https://example.com/credentials/status/{}", n)
+}
+
+//Il clock di tokio è fermo: le scadenze dipendono solo da `advance`, non dal carico della macchina
+#[tokio::test(start_paused = true)]
+async fn hits_do_not_fetch_until_the_ttl_expires()
+{
+    let issuer = Issuer::new();
+    let source = CountingSource::default();
+    source.ledger.publish(LIST_ID, &issuer.list(LIST_ID, &[7], DATE)).await.unwrap();
+
+    let cache = ListCache::new(issuer.document.clone(), source.clone()).with_ttl(Duration::from_millis(200));
+    for index in [7, 8, 7, 9]
+    {
+        let verdict = cache.check_status(&issuer.credential(index, LIST_ID)).await;
+        assert_eq!(verdict.is_revoked(), index == 7);
+    }
+    assert_eq!((source.fetches(), cache.len()), (1, 1));
+
+    //Scaduto il TTL viene letta la nuova versione
+    source.ledger.publish(LIST_ID, &issuer.list(LIST_ID, &[7, 8], "2022-02-01T00:00:00Z")).await.unwrap();
+    tokio::time::advance(Duration::from_millis(199)).await;
+    assert!(!cache.check_status(&issuer.credential(8, LIST_ID)).await.is_revoked());
+    assert_eq!(source.fetches(), 1);
+
+    tokio::time::advance(Duration::from_millis(1)).await;
+    assert!(cache.check_status(&issuer.credential(8, LIST_ID)).await.is_revoked());
+    assert_eq!(source.fetches(), 2);
+
+    cache.invalidate(LIST_ID);
+    assert!(cache.is_empty());
+}
+
+#[tokio::test]
+async fn failures_are_not_cached_and_issuers_are_not_mixed()
+{
+    let issuer = Issuer::new();
+    let source = CountingSource::default();
+    let cache = ListCache::new(issuer.document.clone(), source.clone());
+
+    let verdict = cache.check_status(&issuer.credential(7, LIST_ID)).await;
+    assert!(matches!(verdict, StatusVerdict::Unknown { .. }));
+    assert!(cache.is_empty());
+
+    source.ledger.publish(LIST_ID, &issuer.list(LIST_ID, &[7], DATE)).await.unwrap();
+    assert!(cache.check_status(&issuer.credential(7, LIST_ID)).await.is_revoked());
+
+    //Una credenziale di un altro issuer che punta alla stessa lista non usa la versione in cache
+    let other = Issuer::new();
+    let verdict = cache.check_status(&other.credential(7, LIST_ID)).await;
+    assert!(matches!(verdict, StatusVerdict::Unknown { .. }));
+}
+
+#[tokio::test]
+async fn least_recently_used_lists_are_evicted()
+{
+    let issuer = Issuer::new();
+    let source = CountingSource::default();
+    for n in 0..3
+    {
+        source.ledger.publish(&list_id(n), &issuer.list(&list_id(n), &[], DATE)).await.unwrap();
+    }
+
+    let cache = ListCache::new(issuer.document.clone(), source.clone()).with_max_entries(2);
+    for n in [0, 1, 0, 2]
+    {
+        cache.check_status(&issuer.credential(1, &list_id(n))).await;
+    }
+    assert_eq!((cache.len(), source.fetches()), (2, 3));
+
+    //La lista 1 era la meno usata ed è stata rimossa, la 0 è ancora in cache
+    cache.check_status(&issuer.credential(1, &list_id(0))).await;
+    assert_eq!(source.fetches(), 3);
+    cache.check_status(&issuer.credential(1, &list_id(1))).await;
+    assert_eq!(source.fetches(), 4);
+}
+
+#[tokio::test(start_paused = true)]
+async fn background_refresh_picks_up_new_versions()
+{
+    let issuer = Issuer::new();
+    let source = CountingSource::default();
+    source.ledger.publish(LIST_ID, &issuer.list(LIST_ID, &[], DATE)).await.unwrap();
+
+    let cache = Arc::new(
+        ListCache::new(issuer.document.clone(), source.clone())
+            .with_ttl(Duration::from_secs(60))
+            .with_refresh_ahead(Duration::from_secs(60)),
+    );
+    assert!(!cache.check_status(&issuer.credential(7, LIST_ID)).await.is_revoked());
+
+    source.ledger.publish(LIST_ID, &issuer.list(LIST_ID, &[7], "2022-02-01T00:00:00Z")).await.unwrap();
+    let task = cache.spawn_refresh(Duration::from_secs(10));
+
+    //Con il clock fermo il tempo avanza solo quando tutti i task sono in attesa: prima tick immediata più due periodi.
+    //Solo la prima trova una pubblicazione nuova e riscarica la lista, le altre leggono gli id e basta
+    tokio::time::sleep(Duration::from_secs(25)).await;
+    task.abort();
+
+    let fetches = source.fetches();
+    assert_eq!((fetches, source.lookups()), (2, 3));
+    assert!(cache.check_status(&issuer.credential(7, LIST_ID)).await.is_revoked());
+    assert_eq!(source.fetches(), fetches);
+}
+
+#[tokio::test(start_paused = true)]
+async fn unchanged_lists_are_not_downloaded_again()
+{
+    let issuer = Issuer::new();
+    let source = CountingSource::default();
+    source.ledger.publish(LIST_ID, &issuer.list(LIST_ID, &[7], DATE)).await.unwrap();
+
+    let cache = ListCache::new(issuer.document.clone(), source.clone()).with_ttl(Duration::from_secs(60));
+    assert!(cache.check_status(&issuer.credential(7, LIST_ID)).await.is_revoked());
+
+    //Scaduto il TTL senza nuove pubblicazioni la lista in cache viene rinnovata senza scaricarla
+    tokio::time::advance(Duration::from_secs(60)).await;
+    assert!(cache.check_status(&issuer.credential(7, LIST_ID)).await.is_revoked());
+    assert_eq!((source.fetches(), source.lookups()), (1, 1));
+
+    tokio::time::advance(Duration::from_secs(60)).await;
+    assert_eq!(cache.refresh().await, 1);
+    assert_eq!((source.fetches(), source.lookups()), (1, 2));
+
+    //Il rinnovo vale un altro TTL
+    assert!(cache.check_status(&issuer.credential(7, LIST_ID)).await.is_revoked());
+    assert_eq!(source.lookups(), 2);
+
+    //Una nuova pubblicazione viene scaricata e verificata
+    source.ledger.publish(LIST_ID, &issuer.list(LIST_ID, &[], "2022-02-01T00:00:00Z")).await.unwrap();
+    assert_eq!(cache.refresh().await, 0);
+    tokio::time::advance(Duration::from_secs(60)).await;
+    assert_eq!(cache.refresh().await, 1);
+    assert_eq!((source.fetches(), source.lookups()), (2, 3));
+    assert!(!cache.check_status(&issuer.credential(7, LIST_ID)).await.is_revoked());
+}
+
+#[test]
+fn max_age_follows_the_list_validity()
+{
+    let issuer = Issuer::new();
+    let ttl = Duration::from_secs(3600);
+    let in_seconds = |s: i64| Timestamp::from_unix(Timestamp::now_utc().to_unix() + s).unwrap();
+
+    let mut list = issuer.signed(LIST_ID, &[], DATE);
+    assert_eq!(max_age(&list, ttl), ttl);
+
+    list.expiration_date = Some(in_seconds(600));
+    assert!(max_age(&list, ttl) <= Duration::from_secs(600) && max_age(&list, ttl) >= Duration::from_secs(598));
+
+    list.properties.insert("validUntil".to_owned(), Value::String(in_seconds(60).to_string()));
+    assert!(max_age(&list, ttl) <= Duration::from_secs(60));
+
+    list.expiration_date = Some(in_seconds(-10));
+    assert_eq!(max_age(&list, ttl), Duration::ZERO);
+}
+
+#[tokio::test]
+async fn reads_legacy_lists_with_the_configured_bit_order()
+{
+    let issuer = Issuer::new();
+    let source = CountingSource::default();
+    source.ledger.publish(LIST_ID, &issuer.legacy_list(LIST_ID, &[7], DATE)).await.unwrap();
+
+    let cache = ListCache::new(issuer.document.clone(), source.clone()).with_bit_order(BitOrder::Lsb0);
+    assert!(cache.check_status(&issuer.credential(7, LIST_ID)).await.is_revoked());
+    assert!(!cache.check_status(&issuer.credential(0, LIST_ID)).await.is_revoked());
+    assert_eq!(source.fetches(), 1);
+}
@@ -0,0 +1,62 @@
+#![allow(non_snake_case)]
+
+use identity_core::common::Url;
+use identity_core::common::Value;
+use identity_core::convert::FromJson;
+use identity_core::convert::ToJson;
+use identity_credential::credential::Credential;
+
+use ::RevocationList2020::RevocationList2020;
+use ::RevocationList2020::RevocationList2020Credential::RevocationList2020Credential;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+const ISSUER: &str = "did:iota:FGZTMJSQZoGJxE416TmFnEjCDRJWFE5uTmEMEm5vBypv";
+
+fn list_credential() -> Credential
+{
+    let mut rl = RevocationList2020::new(LIST_ID, 16).unwrap();
+    rl.revoke_many(vec![7, 2500]).unwrap();
+
+    RevocationList2020Credential::new(Url::parse(ISSUER).unwrap(), rl).unwrap().into_credential()
+}
+
+#[test]
+fn round_trips_through_json()
+{
+    let json = list_credential().to_json().unwrap();
+    let parsed = RevocationList2020Credential::try_from(Credential::from_json(&json).unwrap()).unwrap();
+
+    assert_eq!(parsed.id(), LIST_ID);
+    assert_eq!(parsed.issuer().as_str(), ISSUER);
+    assert!(parsed.list().is_revoked(7).unwrap());
+    assert!(parsed.list().is_revoked(2500).unwrap());
+    assert!(!parsed.list().is_revoked(100).unwrap());
+
+    let subject = &parsed.credential().credential_subject.as_slice()[0];
+    assert_eq!(subject.id.as_ref().unwrap().as_str(), "https://example.com/credentials/status/3#list");
+}
+
+#[test]
+fn rejects_malformed_list_credentials()
+{
+    let mut no_context = list_credential();
+    no_context.context = Credential::<()>::base_context().clone().into();
+    assert!(RevocationList2020Credential::try_from(no_context).is_err());
+
+    let mut no_type = list_credential();
+    no_type.types = "VerifiableCredential".to_owned().into();
+    assert!(RevocationList2020Credential::try_from(no_type).is_err());
+
+    let mut no_id = list_credential();
+    no_id.id = None;
+    assert!(RevocationList2020Credential::try_from(no_id).is_err());
+
+    let mut wrong_subject = list_credential();
+    wrong_subject
+        .credential_subject
+        .get_mut(0)
+        .unwrap()
+        .properties
+        .insert("type".to_owned(), Value::String("StatusList2021".to_owned()));
+    assert!(RevocationList2020Credential::try_from(wrong_subject).is_err());
+}
@@ -0,0 +1,74 @@
+#![allow(non_snake_case)]
+
+use identity_core::common::Timestamp;
+
+use ::RevocationList2020::BitOrder;
+use ::RevocationList2020::CredentialError;
+use ::RevocationList2020::ListDiff;
+use ::RevocationList2020::MergePolicy;
+use ::RevocationList2020::RevocationList2020;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+fn list(revoked: &[u64], order: BitOrder) -> RevocationList2020
+{
+    let mut list = RevocationList2020::new(LIST_ID, 16).unwrap().with_bit_order(order);
+    list.revoke_many(revoked.to_vec()).unwrap();
+    list
+}
+
+fn revoked(list: &RevocationList2020) -> Vec<u64>
+{
+    list.iter_revoked().collect()
+}
+
+#[test]
+fn diffs_two_versions()
+{
+    let ours = list(&[1, 7, 64, 500], BitOrder::Msb0);
+    let theirs = list(&[7, 8, 500, 131071], BitOrder::Msb0);
+
+    let diff = ours.diff(&theirs).unwrap();
+    assert_eq!(diff, ListDiff { added: vec![8, 131071], removed: vec![1, 64] });
+    assert!(ours.diff(&ours).unwrap().is_empty());
+
+    //Il confronto avviene sugli indici, non sui byte della bitstring
+    assert!(ours.diff(&list(&[1, 7, 64, 500], BitOrder::Lsb0)).unwrap().is_empty());
+
+    let other_id = RevocationList2020::new("https://example.com/credentials/status/4", 16).unwrap();
+    assert!(matches!(ours.diff(&other_id), Err(CredentialError::ListIdMismatch { .. })));
+    let other_size = RevocationList2020::new(LIST_ID, 32).unwrap();
+    assert!(matches!(ours.diff(&other_size), Err(CredentialError::ListSizeMismatch { expected: 131072, found: 262144 })));
+}
+
+#[test]
+fn merges_with_a_policy()
+{
+    let theirs = list(&[7, 8], BitOrder::Msb0);
+    let older = Timestamp::parse("2022-01-01T00:00:00Z").unwrap();
+    let newer = Timestamp::parse("2022-01-02T00:00:00Z").unwrap();
+
+    //Union: una revoca non viene mai annullata
+    let mut ours = list(&[1, 7], BitOrder::Msb0);
+    assert_eq!(ours.merge(&theirs, MergePolicy::Union).unwrap(), vec![8]);
+    assert_eq!(revoked(&ours), vec![1, 7, 8]);
+
+    let mut ours = list(&[1, 7], BitOrder::Msb0);
+    assert_eq!(ours.merge(&theirs, MergePolicy::PreferNewer { ours: older, theirs: newer }).unwrap(), vec![1, 8]);
+    assert_eq!(revoked(&ours), vec![7, 8]);
+    assert!(ours.is_dirty());
+
+    let mut ours = list(&[1, 7], BitOrder::Msb0);
+    ours.encode().unwrap();
+    assert!(ours.merge(&theirs, MergePolicy::PreferNewer { ours: newer, theirs: older }).unwrap().is_empty());
+    assert_eq!(revoked(&ours), vec![1, 7]);
+    assert!(!ours.is_dirty());
+
+    let mut ours = list(&[1, 7], BitOrder::Msb0);
+    assert_eq!(ours.merge(&theirs, MergePolicy::PreferNewer { ours: newer, theirs: newer }).unwrap(), vec![8]);
+
+    //Liste incompatibili: nessuna modifica
+    let mut ours = list(&[1], BitOrder::Msb0);
+    assert!(ours.merge(&RevocationList2020::new(LIST_ID, 32).unwrap(), MergePolicy::Union).is_err());
+    assert_eq!(revoked(&ours), vec![1]);
+}
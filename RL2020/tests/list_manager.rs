@@ -0,0 +1,163 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use identity_iota::core::Url;
+use identity_iota::credential::Credential;
+use identity_iota::did::DIDUrl;
+use identity_iota::did::DID;
+
+use ::RevocationList2020::BitOrder;
+use ::RevocationList2020::CredentialError;
+use ::RevocationList2020::IssuerStore::{IssuerStore, ListRecord};
+use ::RevocationList2020::Ledger::MemoryLedger;
+use ::RevocationList2020::ListManager::{ListManager, ListSigner};
+use ::RevocationList2020::Result;
+use ::RevocationList2020::Verifier::check_status;
+
+use common::Issuer;
+
+const BASE_ID: &str = "https://example.com/credentials/status";
+
+#[async_trait]
+impl ListSigner for Issuer
+{
+    async fn sign(&self, credential: &mut Credential) -> Result<()>
+    {
+        Issuer::sign(self, credential);
+        Ok(())
+    }
+}
+
+//Registra, per ogni lista salvata, il numero di indici assegnati; con `failing` ogni salvataggio fallisce
+#[derive(Default)]
+struct AllocationLog
+{
+    saved: Mutex<Vec<(String, u64)>>,
+    failing: bool,
+}
+
+impl IssuerStore for AllocationLog
+{
+    fn save(&self, record: &ListRecord) -> Result<()>
+    {
+        if self.failing
+        {
+            return Err(CredentialError::Storage("disk full".to_owned()));
+        }
+        self.saved.lock().unwrap().push((record.list_id().to_owned(), record.allocator.allocated()));
+        Ok(())
+    }
+
+    fn load(&self, _list_id: &str) -> Result<Option<ListRecord>>
+    {
+        Ok(None)
+    }
+
+    fn list_ids(&self) -> Result<Vec<String>>
+    {
+        Ok(Vec::new())
+    }
+
+    fn remove(&self, _list_id: &str) -> Result<bool>
+    {
+        Ok(false)
+    }
+}
+
+#[test]
+fn rolls_over_to_a_new_list_when_full()
+{
+    let store = AllocationLog::default();
+    let mut manager = ListManager::new(BASE_ID, 16).unwrap();
+    let capacity = manager.lists().next().unwrap().capacity() as u64;
+
+    for n in 0..capacity
+    {
+        let (list_id, index) = manager.allocate(&format!("urn:uuid:{}", n), &store).unwrap();
+        assert_eq!((list_id.as_str(), index), ("https://example.com/credentials/status/0", n));
+    }
+
+    let (list_id, index) = manager.allocate("urn:uuid:next", &store).unwrap();
+    assert_eq!((list_id.as_str(), index), ("https://example.com/credentials/status/1", 0));
+    assert_eq!(manager.lists().count(), 2);
+
+    //Ogni indice è stato salvato prima di essere restituito
+    let saved = store.saved.into_inner().unwrap();
+    assert_eq!(saved.len() as u64, capacity + 1);
+    assert_eq!(saved.last().unwrap(), &("https://example.com/credentials/status/1".to_owned(), 1));
+}
+
+#[test]
+fn failed_saves_allocate_nothing()
+{
+    let mut manager = ListManager::new(BASE_ID, 16).unwrap();
+    let failing = AllocationLog { failing: true, ..Default::default() };
+    assert!(matches!(manager.allocate("urn:uuid:alice", &failing), Err(CredentialError::Storage(_))));
+    assert_eq!(manager.slot("urn:uuid:alice"), None);
+
+    //L'indice non consegnato torna disponibile
+    let (list_id, index) = manager.allocate("urn:uuid:alice", &AllocationLog::default()).unwrap();
+    assert_eq!((list_id.as_str(), index), ("https://example.com/credentials/status/0", 0));
+}
+
+#[test]
+fn maps_slots_to_credentials_and_back()
+{
+    let store = AllocationLog::default();
+    let mut manager = ListManager::new(BASE_ID, 16).unwrap();
+    let (list_id, index) = manager.allocate("urn:uuid:alice", &store).unwrap();
+
+    assert_eq!(manager.credential(&list_id, index), Some("urn:uuid:alice"));
+    assert_eq!(manager.slot("urn:uuid:alice"), Some((list_id.as_str(), index)));
+    assert_eq!(manager.slot("urn:uuid:bob"), None);
+
+    //Una credenziale riceve un solo indice
+    assert!(matches!(manager.allocate("urn:uuid:alice", &store), Err(CredentialError::CredentialAlreadyAllocated(id)) if id == "urn:uuid:alice"));
+}
+
+#[test]
+fn revokes_and_resets_by_credential_id()
+{
+    let store = AllocationLog::default();
+    let mut manager = ListManager::new(BASE_ID, 16).unwrap();
+    let (list_id, index) = manager.allocate("urn:uuid:alice", &store).unwrap();
+
+    assert!(manager.revoke("urn:uuid:alice").unwrap());
+    assert!(!manager.revoke("urn:uuid:alice").unwrap());
+    assert!(manager.list(&list_id).unwrap().is_revoked(index).unwrap());
+
+    assert!(manager.reset("urn:uuid:alice").unwrap());
+    assert!(!manager.reset("urn:uuid:alice").unwrap());
+    assert!(matches!(manager.revoke("urn:uuid:bob"), Err(CredentialError::UnknownCredential(id)) if id == "urn:uuid:bob"));
+    assert!(matches!(manager.reset("urn:uuid:bob"), Err(CredentialError::UnknownCredential(_))));
+}
+
+#[tokio::test]
+async fn publishes_only_dirty_lists()
+{
+    let issuer = Issuer::new();
+    let issuer_url = Url::parse(issuer.document.id().as_str()).unwrap();
+    let ledger = MemoryLedger::new();
+
+    let mut manager = ListManager::new(BASE_ID, 16).unwrap();
+    let status = manager.issue_status("urn:uuid:alice", DIDUrl::new(issuer.document.id().clone(), None), &AllocationLog::default()).unwrap();
+    let mut credential = issuer.credential(status.index().unwrap(), status.list_credential().as_str());
+    issuer.sign(&mut credential);
+
+    //Una lista nuova va pubblicata anche se non è stata modificata
+    assert_eq!(manager.dirty_lists(), vec!["https://example.com/credentials/status/0"]);
+    assert_eq!(manager.publish_dirty(&issuer_url, &issuer, &ledger).await.unwrap().len(), 1);
+    assert!(manager.dirty_lists().is_empty());
+    assert!(manager.publish_dirty(&issuer_url, &issuer, &ledger).await.unwrap().is_empty());
+    assert!(!check_status(&credential, &issuer.document, &ledger, BitOrder::Msb0).await.is_revoked());
+
+    manager.revoke("urn:uuid:alice").unwrap();
+    assert_eq!(manager.dirty_lists().len(), 1);
+    manager.publish_dirty(&issuer_url, &issuer, &ledger).await.unwrap();
+    let verdict = check_status(&credential, &issuer.document, &ledger, BitOrder::Msb0).await;
+    assert!(verdict.is_revoked(), "{:?}", verdict);
+}
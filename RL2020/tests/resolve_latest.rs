@@ -0,0 +1,72 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use identity_iota::core::Timestamp;
+use identity_iota::core::Url;
+
+use ::RevocationList2020::Envelope::{encode_credential, Compression};
+use ::RevocationList2020::Ledger::{MemoryLedger, StatusListPublisher};
+use ::RevocationList2020::Verifier::resolve_latest_list;
+use ::RevocationList2020::BitOrder;
+use ::RevocationList2020::CredentialError;
+
+use common::Issuer;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+const INDEX: &str = "RL2020_MyList";
+
+#[tokio::test]
+async fn picks_the_newest_valid_version()
+{
+    let issuer = Issuer::new();
+    let other = Issuer::new();
+    let ledger = MemoryLedger::new();
+
+    ledger.publish(INDEX, &issuer.list(LIST_ID, &[1], "2022-01-01T00:00:00Z")).await.unwrap();
+    ledger.publish(INDEX, &issuer.list(LIST_ID, &[1, 2], "2022-02-01T00:00:00Z")).await.unwrap();
+    //Più recenti ma da scartare: altro issuer, altra lista, payload non valido, firma alterata
+    ledger.publish(INDEX, &other.list(LIST_ID, &[3], "2022-03-01T00:00:00Z")).await.unwrap();
+    ledger.publish(INDEX, &issuer.list("https://example.com/credentials/status/4", &[4], "2022-03-01T00:00:00Z")).await.unwrap();
+    ledger.publish(INDEX, b"not a credential").await.unwrap();
+    let mut tampered = issuer.signed(LIST_ID, &[5], "2022-03-01T00:00:00Z");
+    tampered.issuance_date = Timestamp::parse("2022-04-01T00:00:00Z").unwrap();
+    ledger.publish(INDEX, &encode_credential(&tampered, Compression::Gzip).unwrap()).await.unwrap();
+
+    let latest = resolve_latest_list(INDEX, &Url::parse(LIST_ID).unwrap(), &issuer.document, BitOrder::Msb0, &ledger).await.unwrap();
+    assert_eq!(latest.issuance_date(), Timestamp::parse("2022-02-01T00:00:00Z").unwrap());
+    assert!(latest.list().is_revoked(2).unwrap());
+}
+
+#[tokio::test]
+async fn same_timestamp_is_resolved_independently_of_publication_order()
+{
+    let issuer = Issuer::new();
+    let a = issuer.list(LIST_ID, &[10], "2022-01-01T00:00:00Z");
+    let b = issuer.list(LIST_ID, &[20], "2022-01-01T00:00:00Z");
+    let list_id = Url::parse(LIST_ID).unwrap();
+
+    let mut resolved = Vec::new();
+    for order in [[&a, &b], [&b, &a]]
+    {
+        let ledger = MemoryLedger::new();
+        for payload in order
+        {
+            ledger.publish(INDEX, payload).await.unwrap();
+        }
+        let latest = resolve_latest_list(INDEX, &list_id, &issuer.document, BitOrder::Msb0, &ledger).await.unwrap();
+        resolved.push(latest.list().get_encList().unwrap());
+    }
+    assert_eq!(resolved[0], resolved[1]);
+}
+
+#[tokio::test]
+async fn reports_when_no_valid_version_exists()
+{
+    let issuer = Issuer::new();
+    let ledger = MemoryLedger::new();
+    ledger.publish(INDEX, b"{}").await.unwrap();
+
+    let result = resolve_latest_list(INDEX, &Url::parse(LIST_ID).unwrap(), &issuer.document, BitOrder::Msb0, &ledger).await;
+    assert!(matches!(result, Err(CredentialError::ListNotFound { .. })));
+}
@@ -0,0 +1,163 @@
+#![allow(non_snake_case)]
+
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use flate2::Crc;
+use proptest::prelude::*;
+
+use ::RevocationList2020::BitOrder;
+use ::RevocationList2020::CodingStage;
+use ::RevocationList2020::CredentialError;
+use ::RevocationList2020::RevocationList2020;
+use ::RevocationList2020::Snapshot::SNAPSHOT_VERSION;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+//Lista di dimensione, ordine dei bit e revoche arbitrari, codificata o meno
+fn any_list() -> impl Strategy<Value = RevocationList2020>
+{
+    (
+        "[a-z]{1,8}:[a-zA-Z0-9/._-]{1,64}",
+        16usize..=128,
+        any::<bool>(),
+        prop::collection::vec(any::<u64>(), 0..200),
+        any::<bool>(),
+    )
+        .prop_map(|(id, size, lsb0, indices, encoded)|
+        {
+            let order = if lsb0 { BitOrder::Lsb0 } else { BitOrder::Msb0 };
+            let mut list = RevocationList2020::new(&id, size).unwrap().with_bit_order(order);
+            let capacity = list.capacity() as u64;
+            list.revoke_many(indices.into_iter().map(|index| index % capacity)).unwrap();
+            if encoded
+            {
+                list.encode().unwrap();
+            }
+            list
+        })
+}
+
+//Snapshot ben formato, con checksum corretto, di una bitstring di `bit_len` byte a 0
+fn snapshot_of_size(bit_len: usize) -> Vec<u8>
+{
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&vec![0; bit_len]).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut snapshot = b"RL20\x01\x00".to_vec();
+    snapshot.extend_from_slice(&(LIST_ID.len() as u16).to_le_bytes());
+    snapshot.extend_from_slice(LIST_ID.as_bytes());
+    snapshot.extend_from_slice(&(bit_len as u32).to_le_bytes());
+    snapshot.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+    snapshot.extend_from_slice(&compressed);
+    let mut crc = Crc::new();
+    crc.update(&snapshot);
+    snapshot.extend_from_slice(&crc.sum().to_le_bytes());
+    snapshot
+}
+
+fn snapshot_error(data: &[u8]) -> bool
+{
+    matches!(
+        RevocationList2020::from_snapshot(data),
+        Err(CredentialError::Decoding { stage: CodingStage::Snapshot | CodingStage::Zlib, .. }) | Err(CredentialError::EmptyId)
+    )
+}
+
+proptest!
+{
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn snapshots_round_trip(list in any_list())
+    {
+        let snapshot = list.to_snapshot().unwrap();
+        let loaded = RevocationList2020::from_snapshot(&snapshot).unwrap();
+
+        prop_assert_eq!(loaded.id(), list.id());
+        prop_assert_eq!(loaded.capacity(), list.capacity());
+        prop_assert_eq!(loaded.bit_order(), list.bit_order());
+        prop_assert_eq!(loaded.is_dirty(), list.is_dirty());
+        prop_assert_eq!(loaded.iter_revoked().collect::<Vec<u64>>(), list.iter_revoked().collect::<Vec<u64>>());
+        prop_assert_eq!(loaded.get_encList().unwrap(), list.get_encList().unwrap());
+        prop_assert_eq!(loaded.to_snapshot().unwrap(), snapshot);
+    }
+
+    #[test]
+    fn corrupted_snapshots_are_rejected(
+        list in any_list(),
+        position in any::<prop::sample::Index>(),
+        flip in 1u8..=255,
+        bit_len in 0usize..=129 * 1024,
+    )
+    {
+        let snapshot = list.to_snapshot().unwrap();
+
+        let mut corrupted = snapshot.clone();
+        corrupted[position.index(snapshot.len())] ^= flip;
+        prop_assert!(snapshot_error(&corrupted));
+
+        prop_assert!(snapshot_error(&snapshot[..position.index(snapshot.len())]));
+
+        //Checksum corretto ma bitstring di una dimensione che RevocationList2020::new non accetta
+        if bit_len % 1024 != 0 || !(16..=128).contains(&(bit_len / 1024))
+        {
+            prop_assert!(snapshot_error(&snapshot_of_size(bit_len)));
+        }
+    }
+
+    #[test]
+    fn arbitrary_bytes_do_not_panic(data in prop::collection::vec(any::<u8>(), 0..256))
+    {
+        let _ = RevocationList2020::from_snapshot(&data);
+        let mut prefixed = b"RL20\x01".to_vec();
+        prefixed.extend_from_slice(&data);
+        prop_assert!(RevocationList2020::from_snapshot(&prefixed).is_err());
+    }
+}
+
+#[test]
+fn snapshots_store_the_bitstring_once()
+{
+    let mut list = RevocationList2020::new(LIST_ID, 128).unwrap();
+    list.revoke_many(vec![1, 2, 3, 1000]).unwrap();
+    list.encode().unwrap();
+
+    //Solo la bitstring compressa, senza la encodedList: 128 kb quasi vuoti stanno in poche centinaia di byte
+    let snapshot = list.to_snapshot().unwrap();
+    assert!(snapshot.len() < 512, "{} bytes", snapshot.len());
+    assert_eq!(&snapshot[..5], b"RL20\x01");
+
+    //Stessi limiti di dimensione di RevocationList2020::new
+    assert!(RevocationList2020::from_snapshot(&snapshot_of_size(16 * 1024)).is_ok());
+    assert!(snapshot_error(&snapshot_of_size(0)));
+    assert!(snapshot_error(&snapshot_of_size(15 * 1024)));
+    assert!(snapshot_error(&snapshot_of_size(129 * 1024)));
+
+    let mut future = snapshot.clone();
+    future[4] = SNAPSHOT_VERSION + 1;
+    let error = RevocationList2020::from_snapshot(&future).unwrap_err();
+    assert_eq!(error.to_string(), format!("snapshot decoding failed: unsupported snapshot version {}", SNAPSHOT_VERSION + 1));
+}
+
+#[test]
+fn serde_goes_through_the_snapshot()
+{
+    let mut list = RevocationList2020::new(LIST_ID, 16).unwrap();
+    list.revoke(7).unwrap();
+
+    let json = serde_json::to_string(&list).unwrap();
+    let encoded = base64::encode_config(list.to_snapshot().unwrap(), base64::STANDARD);
+    assert_eq!(json, format!("\"{}\"", encoded));
+
+    let loaded: RevocationList2020 = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.to_snapshot().unwrap(), list.to_snapshot().unwrap());
+
+    //Stessi controlli di from_snapshot: dimensione della bitstring e vecchio formato con encodedList e bitSet
+    let undersized = format!("\"{}\"", base64::encode_config(snapshot_of_size(15 * 1024), base64::STANDARD));
+    assert!(serde_json::from_str::<RevocationList2020>(&undersized).is_err());
+    let legacy = format!(r#"{{"id":"{}","type":"RevocationList2020","encodedList":"{}","bitSet":[0]}}"#, LIST_ID, list.get_encList().unwrap());
+    assert!(serde_json::from_str::<RevocationList2020>(&legacy).is_err());
+}
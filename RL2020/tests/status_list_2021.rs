@@ -0,0 +1,68 @@
+#![allow(non_snake_case)]
+
+use identity_core::common::Object;
+use identity_core::common::Url;
+use identity_core::common::Value;
+use identity_credential::credential::Status;
+use identity_did::did::CoreDID;
+use identity_did::did::DIDUrl;
+
+use ::RevocationList2020::StatusList2021::{StatusList2021, StatusPurpose};
+use ::RevocationList2020::StatusList2021Entry::StatusList2021Entry;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+
+//encodedList dell'esempio nella specifica StatusList2021: 100000 entry, nessuno status settato
+const SPEC_EMPTY_LIST: &str = "H4sIAAAAAAAAA-3BMQEAAADCoPVPbQsvoAAAAAAAAAAAAAAAAP4GcwM92tQwAAA";
+
+#[test]
+fn decodes_spec_example()
+{
+    let sl = StatusList2021::new_withList(LIST_ID, StatusPurpose::Revocation, SPEC_EMPTY_LIST).unwrap();
+
+    assert_eq!(sl.capacity(), 100_000);
+    assert!((0..sl.capacity() as u64).all(|i| !sl.get(i).unwrap()));
+}
+
+#[test]
+fn round_trips_through_gzip()
+{
+    let mut sl = StatusList2021::new(LIST_ID, StatusPurpose::Suspension, 16).unwrap();
+    sl.set(94567, true).unwrap();
+    assert_eq!(sl.apply(vec![(1, true), (94567, true)]).unwrap(), vec![1]);
+
+    let el = sl.encode().unwrap().to_string();
+    assert!(el.starts_with("H4sI"));
+
+    let decoded = StatusList2021::new_withList(LIST_ID, StatusPurpose::Suspension, &el).unwrap();
+    assert!(decoded.get(1).unwrap());
+    assert!(decoded.get(94567).unwrap());
+    assert!(!decoded.get(2).unwrap());
+}
+
+#[test]
+fn entry_round_trips_through_status()
+{
+    let did = CoreDID::parse("did:example:issuer").unwrap();
+    let entry = StatusList2021Entry::new(DIDUrl::new(did, None), StatusPurpose::Revocation, 94567, Url::parse(LIST_ID).unwrap());
+
+    let status: Status = entry.into();
+    assert_eq!(status.type_, "StatusList2021Entry");
+
+    let parsed = StatusList2021Entry::try_from(status).unwrap();
+    assert_eq!(parsed.index().unwrap(), 94567);
+    assert_eq!(parsed.purpose().unwrap(), StatusPurpose::Revocation);
+    assert_eq!(parsed.list_credential().as_str(), LIST_ID);
+}
+
+#[test]
+fn entry_rejects_unknown_purpose()
+{
+    let mut properties = Object::new();
+    properties.insert("statusPurpose".to_owned(), Value::String("refresh".to_owned()));
+    properties.insert("statusListIndex".to_owned(), Value::String("1".to_owned()));
+    properties.insert("statusListCredential".to_owned(), Value::String(LIST_ID.to_owned()));
+    let status = Status::new_with_properties(Url::parse("did:example:issuer").unwrap(), "StatusList2021Entry".to_owned(), properties);
+
+    assert!(StatusList2021Entry::try_from(status).is_err());
+}
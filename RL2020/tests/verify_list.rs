@@ -0,0 +1,63 @@
+#![allow(non_snake_case)]
+
+mod common;
+
+use ::RevocationList2020::Verifier::{verify_list_credential, ListVerdict};
+use ::RevocationList2020::CredentialError;
+
+use common::Issuer;
+
+const LIST_ID: &str = "https://example.com/credentials/status/3";
+const DATE: &str = "2022-01-01T00:00:00Z";
+
+#[test]
+fn accepts_the_list_of_the_credential()
+{
+    let issuer = Issuer::new();
+    let credential = issuer.credential(42, LIST_ID);
+
+    match verify_list_credential(issuer.signed(LIST_ID, &[42], DATE), &credential, &issuer.document).unwrap()
+    {
+        ListVerdict::Valid(list) => assert!(list.list().is_revoked(42).unwrap()),
+        other => panic!("unexpected verdict {:?}", other),
+    }
+}
+
+#[test]
+fn reports_why_a_list_is_rejected()
+{
+    let issuer = Issuer::new();
+    let other = Issuer::new();
+    let credential = issuer.credential(42, LIST_ID);
+
+    //Lista diversa da revocationListCredential
+    let verdict = verify_list_credential(issuer.signed("https://example.com/credentials/status/4", &[], DATE), &credential, &issuer.document);
+    assert!(matches!(verdict, Ok(ListVerdict::WrongList { .. })));
+
+    //Lista emessa da un altro issuer, verificata con il suo o con il nostro DID document
+    for document in [&other.document, &issuer.document]
+    {
+        let verdict = verify_list_credential(other.signed(LIST_ID, &[], DATE), &credential, document);
+        assert!(matches!(verdict, Ok(ListVerdict::WrongIssuer { .. })));
+    }
+
+    //Firma assente o non più valida
+    let mut unsigned = issuer.signed(LIST_ID, &[], DATE);
+    unsigned.proof = None;
+    assert!(matches!(verify_list_credential(unsigned, &credential, &issuer.document), Ok(ListVerdict::InvalidSignature(_))));
+
+    let mut tampered = issuer.signed(LIST_ID, &[], DATE);
+    tampered.types = vec!["VerifiableCredential".to_owned()].into();
+    assert!(matches!(verify_list_credential(tampered.clone(), &credential, &issuer.document), Ok(ListVerdict::InvalidSignature(_))));
+
+    //Firmata correttamente ma senza il tipo RevocationList2020Credential
+    tampered.proof = None;
+    issuer.sign(&mut tampered);
+    assert!(matches!(verify_list_credential(tampered, &credential, &issuer.document), Ok(ListVerdict::Malformed(_))));
+
+    //Credenziale senza status
+    let mut no_status = credential.clone();
+    no_status.credential_status = None;
+    let verdict = verify_list_credential(issuer.signed(LIST_ID, &[], DATE), &no_status, &issuer.document);
+    assert!(matches!(verdict, Err(CredentialError::MissingStatus)));
+}